@@ -0,0 +1,282 @@
+//! Offline decryption of encrypted app tickets.
+//!
+//! Decryption needs the `encrypted-app-ticket` feature, which links `sdkencryptedappticket`
+//! (`sdkencryptedappticket64` on 64-bit Windows) from the Steamworks SDK's redistributable bin
+//! directory. That must be on the linker's search path, e.g. through `cargo:rustc-link-search`
+//! in the depending crate's build script.
+
+#[cfg(feature = "encrypted-app-ticket")]
+use std::{
+	slice,
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+#[cfg(feature = "encrypted-app-ticket")]
+use crate::{EncryptedAppTicket, SteamId};
+
+/// The symmetric key the app's encrypted app tickets are encrypted with,
+/// found on the partner site under the app's SDK Auth settings.
+pub type AppTicketKey = [u8; 32];
+
+/// Parses a key as the partner site shows it, i.e. as 64 hex digits.
+pub fn parse_app_ticket_key(hex: &str) -> Option<AppTicketKey> {
+	let hex = hex.trim();
+	if hex.len() != 64 || !hex.bytes().all(|digit| digit.is_ascii_hexdigit()) {
+		return None;
+	}
+
+	let mut key = [0; 32];
+	for (byte, digits) in key.iter_mut().zip(hex.as_bytes().chunks(2)) {
+		*byte = u8::from_str_radix(std::str::from_utf8(digits).ok()?, 16).ok()?;
+	}
+	Some(key)
+}
+
+#[cfg(feature = "encrypted-app-ticket")]
+/// An encrypted app ticket decrypted with `sdkencryptedappticket`.
+#[derive(Clone, Debug)]
+pub struct DecryptedAppTicket {
+	bytes: Vec<u8>,
+}
+
+#[cfg(feature = "encrypted-app-ticket")]
+impl EncryptedAppTicket {
+	pub fn decrypt(&self, key: &AppTicketKey) -> Option<DecryptedAppTicket> {
+		let mut bytes = vec![0; 1024];
+		let mut len = bytes.len() as u32;
+		if unsafe {
+			SteamEncryptedAppTicket_BDecryptTicket(
+				self.bytes.as_ptr(),
+				self.bytes.len() as u32,
+				bytes.as_mut_ptr(),
+				&mut len as *mut u32,
+				key.as_ptr(),
+				key.len() as i32,
+			)
+		} {
+			bytes.truncate(len as usize);
+			Some(DecryptedAppTicket { bytes })
+		} else {
+			None
+		}
+	}
+
+	/// Decrypts the ticket and checks that it was issued for `appid`.
+	pub fn verify(&self, key: &AppTicketKey, appid: u32) -> Option<DecryptedAppTicket> {
+		self.decrypt(key).filter(|ticket| ticket.is_for_app(appid))
+	}
+}
+
+#[cfg(feature = "encrypted-app-ticket")]
+impl DecryptedAppTicket {
+	pub fn is_for_app(&self, appid: u32) -> bool {
+		unsafe {
+			SteamEncryptedAppTicket_BIsTicketForApp(
+				self.bytes.as_ptr(),
+				self.bytes.len() as u32,
+				appid,
+			)
+		}
+	}
+
+	pub fn app_id(&self) -> u32 {
+		unsafe {
+			SteamEncryptedAppTicket_GetTicketAppID(self.bytes.as_ptr(), self.bytes.len() as u32)
+		}
+	}
+
+	pub fn steam_id(&self) -> SteamId {
+		let mut steam_id = SteamId(0);
+		unsafe {
+			SteamEncryptedAppTicket_GetTicketSteamID(
+				self.bytes.as_ptr(),
+				self.bytes.len() as u32,
+				&mut steam_id as *mut SteamId,
+			)
+		};
+		steam_id
+	}
+
+	pub fn issue_time(&self) -> SystemTime {
+		let secs = unsafe {
+			SteamEncryptedAppTicket_GetTicketIssueTime(self.bytes.as_ptr(), self.bytes.len() as u32)
+		};
+		UNIX_EPOCH + Duration::from_secs(secs.into())
+	}
+
+	pub fn owns_app(&self, appid: u32) -> bool {
+		unsafe {
+			SteamEncryptedAppTicket_BUserOwnsAppInTicket(
+				self.bytes.as_ptr(),
+				self.bytes.len() as u32,
+				appid,
+			)
+		}
+	}
+
+	pub fn is_vac_banned(&self) -> bool {
+		unsafe {
+			SteamEncryptedAppTicket_BUserIsVacBanned(self.bytes.as_ptr(), self.bytes.len() as u32)
+		}
+	}
+
+	/// The data passed to `SteamUser::encrypted_app_ticket` by the client.
+	pub fn user_data(&self) -> &[u8] {
+		let mut len = 0u32;
+		let data = unsafe {
+			SteamEncryptedAppTicket_GetUserVariableData(
+				self.bytes.as_ptr(),
+				self.bytes.len() as u32,
+				&mut len as *mut u32,
+			)
+		};
+		if data.is_null() {
+			&[]
+		} else {
+			unsafe { slice::from_raw_parts(data, len as usize) }
+		}
+	}
+}
+
+#[cfg(feature = "encrypted-app-ticket")]
+#[cfg_attr(
+	all(windows, target_pointer_width = "64"),
+	link(name = "sdkencryptedappticket64")
+)]
+#[cfg_attr(
+	not(all(windows, target_pointer_width = "64")),
+	link(name = "sdkencryptedappticket")
+)]
+extern "C" {}
+
+#[cfg(feature = "encrypted-app-ticket")]
+steam_extern! {
+	fn SteamEncryptedAppTicket_BDecryptTicket(a: *const u8, b: u32, c: *mut u8, d: *mut u32, e: *const u8, f: i32) -> bool;
+
+	fn SteamEncryptedAppTicket_BIsTicketForApp(a: *const u8, b: u32, c: u32)          -> bool;
+	fn SteamEncryptedAppTicket_GetTicketAppID(a: *const u8, b: u32)                   -> u32;
+	fn SteamEncryptedAppTicket_GetTicketSteamID(a: *const u8, b: u32, c: *mut SteamId);
+	fn SteamEncryptedAppTicket_GetTicketIssueTime(a: *const u8, b: u32)               -> u32;
+	fn SteamEncryptedAppTicket_BUserOwnsAppInTicket(a: *const u8, b: u32, c: u32)     -> bool;
+	fn SteamEncryptedAppTicket_BUserIsVacBanned(a: *const u8, b: u32)                 -> bool;
+	fn SteamEncryptedAppTicket_GetUserVariableData(a: *const u8, b: u32, c: *mut u32) -> *const u8;
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const KEY: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191A1B1C1D1E1F";
+
+	#[test]
+	fn parses_hex_keys() {
+		let mut expected = [0; 32];
+		for (i, byte) in expected.iter_mut().enumerate() {
+			*byte = i as u8;
+		}
+
+		assert_eq!(parse_app_ticket_key(KEY), Some(expected));
+		assert_eq!(parse_app_ticket_key(&format!(" {}\n", KEY)), Some(expected));
+	}
+
+	#[test]
+	fn rejects_keys_of_other_lengths() {
+		assert_eq!(parse_app_ticket_key(""), None);
+		assert_eq!(parse_app_ticket_key(&KEY[..62]), None);
+		assert_eq!(parse_app_ticket_key(&format!("{}00", KEY)), None);
+	}
+
+	#[test]
+	fn rejects_keys_with_other_digits() {
+		assert_eq!(parse_app_ticket_key(&KEY.replace("0a", "0g")), None);
+		assert_eq!(parse_app_ticket_key(&KEY.replace("0a", "+a")), None);
+		assert_eq!(parse_app_ticket_key(&KEY.replace("0a0b", "éé")), None);
+	}
+}
+
+/// Tickets are encrypted with the app's secret key, so no fixture can be checked in. Run these
+/// with `cargo test --features encrypted-app-ticket -- --ignored` and a ticket of your own app,
+/// which also needs `sdkencryptedappticket` to be linkable:
+///
+/// - `APP_TICKET_FIXTURE`: the path of the raw ticket, as `EncryptedAppTicket::bytes`
+/// - `APP_TICKET_KEY`: the app's key, in hex
+/// - `APP_TICKET_APPID`: the app the ticket was requested for
+/// - `APP_TICKET_STEAMID`: the 64-bit SteamID of the user who requested it
+#[cfg(all(test, feature = "encrypted-app-ticket"))]
+mod fixture_tests {
+	use std::{env, fs, time::SystemTime};
+
+	use super::*;
+
+	struct Fixture {
+		ticket:   EncryptedAppTicket,
+		key:      AppTicketKey,
+		appid:    u32,
+		steam_id: SteamId,
+	}
+
+	fn var(name: &str) -> String {
+		env::var(name).unwrap_or_else(|_| panic!("{} is not set", name))
+	}
+
+	fn fixture() -> Fixture {
+		let bytes = fs::read(var("APP_TICKET_FIXTURE")).unwrap();
+
+		Fixture {
+			ticket:   EncryptedAppTicket { bytes },
+			key:      parse_app_ticket_key(&var("APP_TICKET_KEY"))
+				.expect("the key must be 32 bytes of hex"),
+			appid:    var("APP_TICKET_APPID").parse().unwrap(),
+			steam_id: SteamId(var("APP_TICKET_STEAMID").parse().unwrap()),
+		}
+	}
+
+	#[test]
+	#[ignore]
+	fn decrypts_with_the_right_key() {
+		let fixture = fixture();
+		let ticket = fixture.ticket.decrypt(&fixture.key).unwrap();
+
+		assert_eq!(ticket.app_id(), fixture.appid);
+		assert!(ticket.is_for_app(fixture.appid));
+		assert!(ticket.owns_app(fixture.appid));
+		assert_eq!(ticket.steam_id(), fixture.steam_id);
+		assert!(ticket.issue_time() <= SystemTime::now());
+		assert!(fixture.ticket.verify(&fixture.key, fixture.appid).is_some());
+	}
+
+	#[test]
+	#[ignore]
+	fn rejects_a_wrong_key() {
+		let fixture = fixture();
+		let mut key = fixture.key;
+		key[0] ^= 0xFF;
+
+		assert!(fixture.ticket.decrypt(&key).is_none());
+		assert!(fixture.ticket.verify(&key, fixture.appid).is_none());
+	}
+
+	#[test]
+	#[ignore]
+	fn rejects_a_wrong_app_id() {
+		let fixture = fixture();
+		let ticket = fixture.ticket.decrypt(&fixture.key).unwrap();
+
+		assert!(!ticket.is_for_app(fixture.appid + 1));
+		assert!(fixture
+			.ticket
+			.verify(&fixture.key, fixture.appid + 1)
+			.is_none());
+	}
+
+	#[test]
+	#[ignore]
+	fn rejects_a_truncated_ticket() {
+		let fixture = fixture();
+		let mut ticket = fixture.ticket;
+		let len = ticket.bytes.len() / 2;
+		ticket.bytes.truncate(len);
+
+		assert!(ticket.decrypt(&fixture.key).is_none());
+	}
+}
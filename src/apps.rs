@@ -0,0 +1,547 @@
+use const_cstr::const_cstr;
+use std::{
+	ffi::CStr,
+	net::{IpAddr, SocketAddr},
+	os::raw::c_char,
+	path::PathBuf,
+};
+
+use futures::{stream, task, Async, Future, Poll, Stream};
+
+use crate::{
+	string_from_ptr,
+	APICall,
+	Client,
+	Error,
+	Lobby,
+	MaybeAPICall,
+	MaybeRaw,
+	Pipe,
+	Raw,
+	RawResult,
+	User,
+};
+
+/// A DLC of the running app.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dlc {
+	pub appid:     u32,
+	/// Whether the DLC is available in the store, which it may not be yet or anymore.
+	pub available: bool,
+	pub name:      String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DlcDownloadProgress {
+	pub downloaded: u64,
+	pub total:      u64,
+}
+
+/// A DLC finished installing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DlcInstalled {
+	pub appid: u32,
+}
+
+declare_notification! {
+	Data (1005) {
+		appid: u32,
+	} -> DlcInstalled;
+
+	map(|Data {appid}| DlcInstalled {appid});
+}
+
+/// What Steam ships for a file of the app.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FileDetails {
+	pub size:  u64,
+	pub sha1:  [u8; 20],
+	pub flags: u32,
+}
+
+/// The app was launched through a URL again while running; see `launch_command_line`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NewUrlLaunchParameters;
+
+declare_notification! {
+	Data (1014) {} -> NewUrlLaunchParameters;
+
+	map(|Data {}| NewUrlLaunchParameters);
+}
+
+/// The port servers are assumed to listen on when `+connect` does not name one.
+pub const DEFAULT_SERVER_PORT: u16 = 27015;
+
+/// What to join, as requested by the `+connect` and `+connect_lobby` launch arguments.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum JoinRequest {
+	Lobby(Lobby),
+	Server {
+		addr:     SocketAddr,
+		password: Option<String>,
+	},
+}
+
+impl JoinRequest {
+	/// Parses a command line such as `+connect 1.2.3.4:27015 +password secret`.
+	pub fn parse(command_line: &str) -> Option<Self> {
+		Self::from_args(split_command_line(command_line))
+	}
+
+	/// Parses already split arguments, e.g. `std::env::args()`.
+	pub fn from_args<I: IntoIterator<Item = S>, S: AsRef<str>>(args: I) -> Option<Self> {
+		let mut lobby = None;
+		let mut addr = None;
+		let mut password = None;
+
+		let mut args = args.into_iter();
+		while let Some(arg) = args.next() {
+			match arg.as_ref() {
+				"+connect_lobby" => {
+					lobby = args
+						.next()
+						.and_then(|id| id.as_ref().parse().ok())
+						.map(Lobby);
+				},
+				"+connect" => {
+					addr = args
+						.next()
+						.and_then(|addr| parse_server_addr(addr.as_ref()));
+				},
+				"+password" => {
+					password = args.next().map(|password| password.as_ref().to_owned());
+				},
+				_ => {},
+			}
+		}
+
+		match (lobby, addr) {
+			(Some(lobby), _) => Some(JoinRequest::Lobby(lobby)),
+			(None, Some(addr)) => Some(JoinRequest::Server { addr, password }),
+			(None, None) => None,
+		}
+	}
+}
+
+fn parse_server_addr(addr: &str) -> Option<SocketAddr> {
+	addr.parse().ok().or_else(|| {
+		addr.parse::<IpAddr>()
+			.ok()
+			.map(|ip| SocketAddr::new(ip, DEFAULT_SERVER_PORT))
+	})
+}
+
+/// Splits a command line at whitespace, keeping double-quoted arguments together.
+///
+/// A backslash makes a following quote or backslash literal.
+fn split_command_line(command_line: &str) -> Vec<String> {
+	let mut args = Vec::new();
+	let mut arg = String::new();
+	let mut quoted = false;
+	let mut started = false;
+
+	let mut chars = command_line.chars().peekable();
+	while let Some(c) = chars.next() {
+		match c {
+			'\\' if matches!(chars.peek(), Some('"') | Some('\\')) => {
+				arg.extend(chars.next());
+				started = true;
+			},
+			'"' => {
+				quoted = !quoted;
+				started = true;
+			},
+			c if c.is_whitespace() && !quoted => {
+				if started {
+					args.push(std::mem::take(&mut arg));
+					started = false;
+				}
+			},
+			c => {
+				arg.push(c);
+				started = true;
+			},
+		}
+	}
+	if started {
+		args.push(arg);
+	}
+
+	args
+}
+
+interface!(Apps);
+impl<'a> Apps<'a> {
+	pub fn new(client: &Client<'a>) -> Option<Self> {
+		let raw = unsafe {
+			SteamAPI_ISteamClient_GetISteamApps(
+				client.raw.clone(),
+				client.user(),
+				client.pipe(),
+				const_cstr!("STEAMAPPS_INTERFACE_VERSION008").as_ptr(),
+			)
+			.check()?
+		};
+		let utils = client.utils.clone();
+		let callbacks = client.callbacks.clone();
+
+		Some(Apps {
+			raw,
+			utils,
+			callbacks,
+		})
+	}
+
+	/// Whether the user owns the running app.
+	pub fn is_subscribed(&self) -> bool {
+		unsafe { SteamAPI_ISteamApps_BIsSubscribed(self.raw.clone()) }
+	}
+
+	/// Whether the user owns `appid`, which should be one of the running app's DLCs or related apps.
+	pub fn is_subscribed_app(&self, appid: u32) -> bool {
+		unsafe { SteamAPI_ISteamApps_BIsSubscribedApp(self.raw.clone(), appid) }
+	}
+
+	pub fn is_dlc_installed(&self, appid: u32) -> bool {
+		unsafe { SteamAPI_ISteamApps_BIsDlcInstalled(self.raw.clone(), appid) }
+	}
+
+	pub fn dlc_count(&self) -> u32 {
+		let count = unsafe { SteamAPI_ISteamApps_GetDLCCount(self.raw.clone()) };
+		count.max(0) as u32
+	}
+
+	pub fn dlc_by_index(&self, index: u32) -> Option<Dlc> {
+		let mut appid = 0;
+		let mut available = false;
+		let mut name = [0 as c_char; 128];
+		if unsafe {
+			SteamAPI_ISteamApps_BGetDLCDataByIndex(
+				self.raw.clone(),
+				index as i32,
+				&mut appid,
+				&mut available,
+				name.as_mut_ptr(),
+				name.len() as i32,
+			)
+		} {
+			Some(Dlc {
+				appid,
+				available,
+				name: string_from_ptr(name.as_ptr()).unwrap_or_default(),
+			})
+		} else {
+			None
+		}
+	}
+
+	pub fn dlcs(&self) -> impl Iterator<Item = Dlc> + '_ {
+		(0..self.dlc_count()).filter_map(move |i| self.dlc_by_index(i))
+	}
+
+	pub fn install_dlc(&self, appid: u32) {
+		unsafe { SteamAPI_ISteamApps_InstallDLC(self.raw.clone(), appid) }
+	}
+
+	pub fn uninstall_dlc(&self, appid: u32) {
+		unsafe { SteamAPI_ISteamApps_UninstallDLC(self.raw.clone(), appid) }
+	}
+
+	/// The download progress of a DLC, if it is being downloaded.
+	pub fn dlc_download_progress(&self, appid: u32) -> Option<DlcDownloadProgress> {
+		let mut downloaded = 0;
+		let mut total = 0;
+		if unsafe {
+			SteamAPI_ISteamApps_GetDlcDownloadProgress(
+				self.raw.clone(),
+				appid,
+				&mut downloaded,
+				&mut total,
+			)
+		} {
+			Some(DlcDownloadProgress { downloaded, total })
+		} else {
+			None
+		}
+	}
+
+	/// Polls the download progress of a DLC, yielding on changes and ending once it is installed.
+	///
+	/// Steam reports no progress until the download starts, so call this after `install_dlc`.
+	pub fn dlc_download_progress_updates(
+		&'a self,
+		appid: u32,
+	) -> impl Stream<Item = DlcDownloadProgress, Error = Error> + 'a {
+		let mut installs = self.dlc_installs();
+		let mut last = None;
+		stream::poll_fn(move || {
+			while let Async::Ready(installed) = installs.poll()? {
+				match installed {
+					Some(installed) if installed.appid != appid => continue,
+					_ => return Ok(Async::Ready(None)),
+				}
+			}
+			if self.is_dlc_installed(appid) {
+				return Ok(Async::Ready(None));
+			}
+
+			match self.dlc_download_progress(appid) {
+				Some(progress) if last != Some(progress) => {
+					last = Some(progress);
+					Ok(Async::Ready(Some(progress)))
+				},
+				_ => {
+					task::current().notify();
+					Ok(Async::NotReady)
+				},
+			}
+		})
+	}
+
+	pub fn dlc_installs(&self) -> impl Stream<Item = DlcInstalled, Error = Error> + 'a {
+		self.callbacks.subscribe()
+	}
+
+	/// The size, SHA-1 and flags Steam ships for a file, relative to the install directory.
+	pub fn file_details(
+		&'a self,
+		name: &CStr,
+	) -> Option<impl Future<Item = FileDetails, Error = Error> + 'a> {
+		declare_future! {
+			Data (1023) {
+				result: RawResult,
+				size:   u64,
+				sha1:   [u8; 20],
+				flags:  u32,
+			} -> FileDetails;
+
+			map(|Data {result, size, sha1, flags}| {
+				Result::from(result).map(|()| FileDetails {size, sha1, flags})
+			});
+		}
+
+		let api_call =
+			unsafe { SteamAPI_ISteamApps_GetFileDetails(self.raw.clone(), name.as_ptr()) };
+
+		Some(Handle {
+			api_call: unsafe { APICall::new(api_call)? },
+			utils:    self.utils.clone(),
+		})
+	}
+
+	/// The beta branch the app is running on, `None` for the default branch.
+	pub fn current_beta_name(&self) -> Option<String> {
+		let mut name = [0 as c_char; 128];
+		if unsafe {
+			SteamAPI_ISteamApps_GetCurrentBetaName(
+				self.raw.clone(),
+				name.as_mut_ptr(),
+				name.len() as i32,
+			)
+		} {
+			string_from_ptr(name.as_ptr())
+		} else {
+			None
+		}
+	}
+
+	/// The language the user chose for the app, e.g. `english`.
+	pub fn current_game_language(&self) -> String {
+		string_from_ptr(unsafe { SteamAPI_ISteamApps_GetCurrentGameLanguage(self.raw.clone()) })
+			.unwrap_or_default()
+	}
+
+	pub fn available_game_languages(&self) -> Vec<String> {
+		string_from_ptr(unsafe { SteamAPI_ISteamApps_GetAvailableGameLanguages(self.raw.clone()) })
+			.unwrap_or_default()
+			.split(',')
+			.filter(|language| !language.is_empty())
+			.map(String::from)
+			.collect()
+	}
+
+	/// Where `appid` is installed, even if it is not fully installed.
+	pub fn app_install_dir(&self, appid: u32) -> Option<PathBuf> {
+		let mut folder = vec![0 as c_char; 4096];
+		let len = unsafe {
+			SteamAPI_ISteamApps_GetAppInstallDir(
+				self.raw.clone(),
+				appid,
+				folder.as_mut_ptr(),
+				folder.len() as u32,
+			)
+		};
+		if len == 0 {
+			return None;
+		}
+
+		string_from_ptr(folder.as_ptr())
+			.filter(|folder| !folder.is_empty())
+			.map(PathBuf::from)
+	}
+
+	/// The command line of the `steam://run/<appid>//<command line>` URL the app was launched with.
+	pub fn launch_command_line(&self) -> String {
+		let mut command_line = vec![0 as c_char; 4096];
+		let len = unsafe {
+			SteamAPI_ISteamApps_GetLaunchCommandLine(
+				self.raw.clone(),
+				command_line.as_mut_ptr(),
+				command_line.len() as i32,
+			)
+		};
+		if len <= 0 {
+			return String::new();
+		}
+
+		string_from_ptr(command_line.as_ptr()).unwrap_or_default()
+	}
+
+	/// The join request of the launch command line, if any.
+	pub fn launch_join_request(&self) -> Option<JoinRequest> {
+		JoinRequest::parse(&self.launch_command_line())
+	}
+
+	pub fn new_url_launches(
+		&self,
+	) -> impl Stream<Item = NewUrlLaunchParameters, Error = Error> + 'a {
+		self.callbacks.subscribe()
+	}
+
+	/// The build the app is running, which is 0 when not launched through Steam.
+	pub fn app_build_id(&self) -> i32 {
+		unsafe { SteamAPI_ISteamApps_GetAppBuildId(self.raw.clone()) }
+	}
+
+	/// A parameter of the `steam://run/<appid>//?<params>` URL the app was launched with.
+	///
+	/// Only keys starting with `@` are passed on by Steam.
+	pub fn launch_query_param(&self, key: &CStr) -> Option<String> {
+		string_from_ptr(unsafe {
+			SteamAPI_ISteamApps_GetLaunchQueryParam(self.raw.clone(), key.as_ptr())
+		})
+		.filter(|value| !value.is_empty())
+	}
+}
+
+steam_extern! {
+	fn SteamAPI_ISteamClient_GetISteamApps<'a>(a: Raw<Client<'a>>, b: User<'a>, c: Pipe<'a>, d: *const c_char) -> MaybeRaw<Apps<'a>>;
+
+	fn SteamAPI_ISteamApps_BIsSubscribed(a: Raw<Apps<'_>>)             -> bool;
+	fn SteamAPI_ISteamApps_BIsSubscribedApp(a: Raw<Apps<'_>>, b: u32) -> bool;
+	fn SteamAPI_ISteamApps_BIsDlcInstalled(a: Raw<Apps<'_>>, b: u32)  -> bool;
+
+	fn SteamAPI_ISteamApps_GetDLCCount(a: Raw<Apps<'_>>) -> i32;
+	fn SteamAPI_ISteamApps_BGetDLCDataByIndex(a: Raw<Apps<'_>>, b: i32, c: *mut u32, d: *mut bool, e: *mut c_char, f: i32) -> bool;
+	fn SteamAPI_ISteamApps_InstallDLC(a: Raw<Apps<'_>>, b: u32);
+	fn SteamAPI_ISteamApps_UninstallDLC(a: Raw<Apps<'_>>, b: u32);
+	fn SteamAPI_ISteamApps_GetDlcDownloadProgress(a: Raw<Apps<'_>>, b: u32, c: *mut u64, d: *mut u64) -> bool;
+
+	fn SteamAPI_ISteamApps_GetFileDetails(a: Raw<Apps<'_>>, b: *const c_char) -> MaybeAPICall;
+
+	fn SteamAPI_ISteamApps_GetCurrentBetaName(a: Raw<Apps<'_>>, b: *mut c_char, c: i32) -> bool;
+	fn SteamAPI_ISteamApps_GetCurrentGameLanguage(a: Raw<Apps<'_>>)                     -> *const c_char;
+	fn SteamAPI_ISteamApps_GetAvailableGameLanguages(a: Raw<Apps<'_>>)                  -> *const c_char;
+	fn SteamAPI_ISteamApps_GetAppInstallDir(a: Raw<Apps<'_>>, b: u32, c: *mut c_char, d: u32) -> u32;
+	fn SteamAPI_ISteamApps_GetAppBuildId(a: Raw<Apps<'_>>)                              -> i32;
+	fn SteamAPI_ISteamApps_GetLaunchCommandLine(a: Raw<Apps<'_>>, b: *mut c_char, c: i32) -> i32;
+	fn SteamAPI_ISteamApps_GetLaunchQueryParam(a: Raw<Apps<'_>>, b: *const c_char)      -> *const c_char;
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn splits_at_whitespace() {
+		assert_eq!(
+			split_command_line("  +connect\t1.2.3.4  "),
+			["+connect", "1.2.3.4"]
+		);
+	}
+
+	#[test]
+	fn splits_empty_input() {
+		assert!(split_command_line("").is_empty());
+		assert!(split_command_line("   ").is_empty());
+	}
+
+	#[test]
+	fn keeps_quoted_arguments_together() {
+		assert_eq!(
+			split_command_line(r#"+password "two words" a"b c"d"#),
+			["+password", "two words", "ab cd"]
+		);
+		assert_eq!(split_command_line(r#"+password """#), ["+password", ""]);
+	}
+
+	#[test]
+	fn unescapes_quotes_and_backslashes() {
+		assert_eq!(
+			split_command_line(r#"+password "say \"hi\"" a\\b c\d"#),
+			["+password", r#"say "hi""#, r"a\b", r"c\d"]
+		);
+	}
+
+	#[test]
+	fn parses_lobby() {
+		assert_eq!(
+			JoinRequest::parse("+connect_lobby 109775241021923456"),
+			Some(JoinRequest::Lobby(Lobby(109775241021923456)))
+		);
+	}
+
+	#[test]
+	fn prefers_lobby_over_server() {
+		assert_eq!(
+			JoinRequest::parse("+connect 1.2.3.4 +connect_lobby 5"),
+			Some(JoinRequest::Lobby(Lobby(5)))
+		);
+	}
+
+	#[test]
+	fn parses_server_with_password() {
+		assert_eq!(
+			JoinRequest::parse(r#"-novid +connect 1.2.3.4:27016 +password "se cret""#),
+			Some(JoinRequest::Server {
+				addr:     "1.2.3.4:27016".parse().unwrap(),
+				password: Some("se cret".to_owned()),
+			})
+		);
+	}
+
+	#[test]
+	fn defaults_server_port() {
+		assert_eq!(
+			JoinRequest::parse("+connect 1.2.3.4"),
+			Some(JoinRequest::Server {
+				addr:     SocketAddr::new([1, 2, 3, 4].into(), DEFAULT_SERVER_PORT),
+				password: None,
+			})
+		);
+	}
+
+	#[test]
+	fn parses_args() {
+		assert_eq!(
+			JoinRequest::from_args(vec!["game", "+connect_lobby", "42"]),
+			Some(JoinRequest::Lobby(Lobby(42)))
+		);
+	}
+
+	#[test]
+	fn rejects_missing_values() {
+		assert_eq!(JoinRequest::parse(""), None);
+		assert_eq!(JoinRequest::parse("+connect_lobby"), None);
+		assert_eq!(JoinRequest::parse("+connect"), None);
+		assert_eq!(JoinRequest::parse("+password secret"), None);
+	}
+
+	#[test]
+	fn rejects_malformed_values() {
+		assert_eq!(JoinRequest::parse("+connect_lobby abc"), None);
+		assert_eq!(JoinRequest::parse("+connect_lobby -1"), None);
+		assert_eq!(JoinRequest::parse("+connect example.com"), None);
+		assert_eq!(JoinRequest::parse("+connect 1.2.3.4:port"), None);
+	}
+}
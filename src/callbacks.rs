@@ -0,0 +1,124 @@
+use std::{
+	cell::RefCell,
+	collections::{HashMap, VecDeque},
+	marker::PhantomData,
+	mem::zeroed,
+	rc::{Rc, Weak},
+	slice,
+};
+
+use futures::{task, Async, Poll, Stream};
+
+use crate::{Error, Pipe, User};
+
+/// A callback posted by Steam, decoded from its raw payload.
+pub trait Notification: Sized {
+	const ID: u32;
+
+	/// # Safety
+	/// `raw` must be the payload of a callback with the id `ID`.
+	unsafe fn from_raw(raw: &[u8]) -> Option<Self>;
+}
+
+type Queue = RefCell<VecDeque<Box<[u8]>>>;
+
+#[repr(C)]
+struct CallbackMsg<'a> {
+	user:      User<'a>,
+	id:        i32,
+	param:     *const u8,
+	param_len: i32,
+}
+
+/// Dispatches Steam callbacks to the `Notifications` streams subscribed to them.
+///
+/// Callbacks are only queued for streams that exist when they are dispatched,
+/// so subscribe before making the call that triggers them.
+#[derive(Clone)]
+pub struct Callbacks<'a> {
+	pipe:   Pipe<'a>,
+	queues: Rc<RefCell<HashMap<u32, Vec<Weak<Queue>>>>>,
+}
+
+impl<'a> Callbacks<'a> {
+	pub(crate) fn new(pipe: Pipe<'a>) -> Self {
+		Callbacks {
+			pipe,
+			queues: Rc::new(RefCell::new(HashMap::new())),
+		}
+	}
+
+	/// Pumps Steam's callback queue. Polling a `Notifications` stream does this as well.
+	pub fn run(&self) {
+		unsafe { SteamAPI_ManualDispatch_RunFrame(self.pipe) };
+
+		let mut msg: CallbackMsg<'_> = unsafe { zeroed() };
+		while unsafe { SteamAPI_ManualDispatch_GetNextCallback(self.pipe, &mut msg as *mut _) } {
+			let raw = unsafe { slice::from_raw_parts(msg.param, msg.param_len as usize) };
+			self.dispatch(msg.id as u32, raw);
+			unsafe { SteamAPI_ManualDispatch_FreeLastCallback(self.pipe) };
+		}
+	}
+
+	pub(crate) fn dispatch(&self, id: u32, raw: &[u8]) {
+		if let Some(subscribers) = self.queues.borrow_mut().get_mut(&id) {
+			subscribers.retain(|queue| match queue.upgrade() {
+				Some(queue) => {
+					queue.borrow_mut().push_back(raw.into());
+					true
+				},
+				None => false,
+			});
+		}
+	}
+
+	pub fn subscribe<T: Notification>(&self) -> Notifications<'a, T> {
+		let queue = Rc::new(RefCell::new(VecDeque::new()));
+		self.queues
+			.borrow_mut()
+			.entry(T::ID)
+			.or_default()
+			.push(Rc::downgrade(&queue));
+
+		Notifications {
+			callbacks: self.clone(),
+			queue,
+			_marker: PhantomData,
+		}
+	}
+}
+
+/// A never-ending stream of the callbacks of type `T`.
+pub struct Notifications<'a, T> {
+	callbacks: Callbacks<'a>,
+	queue:     Rc<Queue>,
+	_marker:   PhantomData<T>,
+}
+
+impl<T: Notification> Stream for Notifications<'_, T> {
+	type Error = Error;
+	type Item = T;
+
+	fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+		if self.queue.borrow().is_empty() {
+			self.callbacks.run();
+		}
+
+		let raw = self.queue.borrow_mut().pop_front();
+		match raw {
+			Some(raw) => unsafe { T::from_raw(&raw) }
+				.map(|n| Async::Ready(Some(n)))
+				.ok_or(Error::Fail),
+			None => {
+				task::current().notify();
+				Ok(Async::NotReady)
+			},
+		}
+	}
+}
+
+steam_extern! {
+	fn SteamAPI_ManualDispatch_RunFrame(a: Pipe<'_>);
+	fn SteamAPI_ManualDispatch_GetNextCallback(a: Pipe<'_>, b: *mut CallbackMsg<'_>) -> bool;
+	fn SteamAPI_ManualDispatch_FreeLastCallback(a: Pipe<'_>);
+}
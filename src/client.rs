@@ -1,7 +1,12 @@
-use crate::{MaybeRaw, Raw, Utils};
+use crate::{Callbacks, Interface, MaybeRaw, Raw, Utils};
 use const_cstr::const_cstr;
 use lazy_static::lazy_static;
-use std::{ffi::c_void, marker::PhantomData, os::raw::c_char, sync::Mutex};
+use std::{
+	ffi::{c_void, CStr},
+	marker::PhantomData,
+	os::raw::c_char,
+	sync::Mutex,
+};
 
 pub struct Steam {}
 lazy_static! {
@@ -13,6 +18,7 @@ impl Steam {
 		if unsafe { !SteamAPI_Init() } {
 			return None;
 		}
+		unsafe { SteamAPI_ManualDispatch_Init() };
 
 		let raw: MaybeRaw<_> =
 			unsafe { SteamInternal_CreateInterface(const_cstr!("SteamClient017").as_ptr()) }.into();
@@ -22,17 +28,24 @@ impl Steam {
 			SteamAPI_ISteamClient_GetISteamUtils(
 				raw.clone(),
 				SteamAPI_GetHSteamPipe(),
-				const_cstr!("SteamUtils009").as_ptr(),
+				const_cstr!("SteamUtils010").as_ptr(),
 			)
 			.check()?
 		};
 
+		let callbacks = Callbacks::new(unsafe { SteamAPI_GetHSteamPipe() });
+
 		let utils = Utils {
-			raw:     utils,
-			_marker: PhantomData,
+			raw:       utils,
+			callbacks: callbacks.clone(),
+			_marker:   PhantomData,
 		};
 
-		Some(Client { raw, utils })
+		Some(Client {
+			raw,
+			utils,
+			callbacks,
+		})
 	}
 }
 
@@ -45,8 +58,9 @@ pub struct User<'a>(i32, PhantomData<&'a ()>);
 pub struct Pipe<'a>(i32, PhantomData<&'a ()>);
 
 pub struct Client<'a> {
-	pub(crate) raw:   Raw<Client<'a>>,
-	pub(crate) utils: Utils<'a>,
+	pub(crate) raw:       Raw<Client<'a>>,
+	pub(crate) utils:     Utils<'a>,
+	pub(crate) callbacks: Callbacks<'a>,
 }
 impl crate::Interface for Client<'_> {}
 
@@ -58,6 +72,25 @@ impl<'a> Client<'a> {
 	pub fn pipe(&self) -> Pipe<'a> {
 		unsafe { SteamAPI_GetHSteamPipe() }
 	}
+
+	/// Looks up an interface that has no dedicated `ISteamClient` accessor.
+	pub(crate) fn generic_interface<T: Interface>(&self, version: &CStr) -> Option<Raw<T>> {
+		let raw: MaybeRaw<_> = unsafe {
+			SteamAPI_ISteamClient_GetISteamGenericInterface(
+				self.raw.clone(),
+				self.user(),
+				self.pipe(),
+				version.as_ptr(),
+			)
+		}
+		.into();
+		raw.check()
+	}
+
+	/// Dispatches pending callbacks; call this regularly, e.g. once per frame.
+	pub fn run_callbacks(&self) {
+		self.callbacks.run()
+	}
 }
 
 impl Drop for Client<'_> {
@@ -69,11 +102,13 @@ impl Drop for Client<'_> {
 steam_extern! {
 	fn SteamAPI_Init() -> bool;
 	fn SteamAPI_Shutdown();
+	fn SteamAPI_ManualDispatch_Init();
 
 	fn SteamAPI_GetHSteamUser<'a>() -> User<'a>;
 	fn SteamAPI_GetHSteamPipe<'a>() -> Pipe<'a>;
 
 	fn SteamAPI_ISteamClient_GetISteamUtils<'a>(a: Raw<Client<'a>>, b: Pipe<'_>, c: *const c_char) -> MaybeRaw<Utils<'a>>;
+	fn SteamAPI_ISteamClient_GetISteamGenericInterface<'a>(a: Raw<Client<'a>>, b: User<'a>, c: Pipe<'a>, d: *const c_char) -> *mut c_void;
 
 	fn SteamInternal_CreateInterface(a: *const c_char) -> *mut c_void;
 }
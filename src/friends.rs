@@ -0,0 +1,532 @@
+use const_cstr::const_cstr;
+use derive_more::Display;
+use std::{
+	ffi::{CStr, CString},
+	ops::BitOr,
+	os::raw::c_char,
+};
+
+use futures::{
+	future::{self, Either},
+	Future,
+	Stream,
+};
+
+use crate::{
+	string_from_ptr,
+	Client,
+	Error,
+	Image,
+	ImageHandle,
+	MaybeRaw,
+	Notifications,
+	Pipe,
+	Raw,
+	SteamId,
+	User,
+};
+
+/// Which relationships to include when listing friends.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FriendFlags(pub u32);
+
+impl FriendFlags {
+	pub const ALL: FriendFlags = FriendFlags(0xFFFF);
+	pub const BLOCKED: FriendFlags = FriendFlags(0x1);
+	pub const CHAT_MEMBER: FriendFlags = FriendFlags(0x1000);
+	pub const CLAN_MEMBER: FriendFlags = FriendFlags(0x8);
+	pub const FRIENDSHIP_REQUESTED: FriendFlags = FriendFlags(0x2);
+	pub const IGNORED: FriendFlags = FriendFlags(0x200);
+	pub const IGNORED_FRIEND: FriendFlags = FriendFlags(0x400);
+	pub const IMMEDIATE: FriendFlags = FriendFlags(0x4);
+	pub const NONE: FriendFlags = FriendFlags(0x0);
+	pub const ON_GAME_SERVER: FriendFlags = FriendFlags(0x10);
+	pub const REQUESTING_FRIENDSHIP: FriendFlags = FriendFlags(0x80);
+	pub const REQUESTING_INFO: FriendFlags = FriendFlags(0x100);
+
+	pub fn contains(self, other: FriendFlags) -> bool {
+		self.0 & other.0 == other.0
+	}
+}
+
+impl BitOr for FriendFlags {
+	type Output = FriendFlags;
+
+	fn bitor(self, other: FriendFlags) -> FriendFlags {
+		FriendFlags(self.0 | other.0)
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PersonaState {
+	Offline,
+	Online,
+	Busy,
+	Away,
+	Snooze,
+	LookingToTrade,
+	LookingToPlay,
+	Invisible,
+}
+
+impl From<u32> for PersonaState {
+	fn from(state: u32) -> Self {
+		match state {
+			1 => PersonaState::Online,
+			2 => PersonaState::Busy,
+			3 => PersonaState::Away,
+			4 => PersonaState::Snooze,
+			5 => PersonaState::LookingToTrade,
+			6 => PersonaState::LookingToPlay,
+			7 => PersonaState::Invisible,
+			_ => PersonaState::Offline,
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AvatarSize {
+	/// 32x32
+	Small,
+	/// 64x64
+	Medium,
+	/// 184x184
+	Large,
+}
+
+/// Posted when the persona of a user changes or their requested information arrives.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PersonaStateChange {
+	pub user:         SteamId,
+	/// The `EPersonaChange` flags of what changed.
+	pub change_flags: u32,
+}
+
+declare_notification! {
+	Data (304) {
+		user:         SteamId,
+		change_flags: u32,
+	} -> PersonaStateChange;
+
+	map(|Data {user, change_flags}| PersonaStateChange {user, change_flags});
+}
+
+struct AvatarImageLoaded {
+	user:  SteamId,
+	image: ImageHandle,
+}
+
+declare_notification! {
+	Data (334) {
+		user:    SteamId,
+		image:   ImageHandle,
+		_width:  i32,
+		_height: i32,
+	} -> AvatarImageLoaded;
+
+	map(|Data {user, image, ..}| AvatarImageLoaded {user, image});
+}
+
+/// Posted when the rich presence of a friend changes or was requested.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FriendRichPresenceUpdate {
+	pub friend: SteamId,
+	pub appid:  u32,
+}
+
+declare_notification! {
+	Data (336) {
+		friend: SteamId,
+		appid:  u32,
+	} -> FriendRichPresenceUpdate;
+
+	map(|Data {friend, appid}| FriendRichPresenceUpdate {friend, appid});
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OverlayDialog {
+	Friends,
+	Community,
+	Players,
+	Settings,
+	OfficialGameGroup,
+	Stats,
+	Achievements,
+}
+
+impl OverlayDialog {
+	fn as_cstr(self) -> &'static CStr {
+		match self {
+			OverlayDialog::Friends => const_cstr!("friends"),
+			OverlayDialog::Community => const_cstr!("community"),
+			OverlayDialog::Players => const_cstr!("players"),
+			OverlayDialog::Settings => const_cstr!("settings"),
+			OverlayDialog::OfficialGameGroup => const_cstr!("officialgamegroup"),
+			OverlayDialog::Stats => const_cstr!("stats"),
+			OverlayDialog::Achievements => const_cstr!("achievements"),
+		}
+		.as_cstr()
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OverlayUserDialog {
+	Profile,
+	Chat,
+	JoinTrade,
+	Stats,
+	Achievements,
+	FriendAdd,
+	FriendRemove,
+	FriendRequestAccept,
+	FriendRequestIgnore,
+}
+
+impl OverlayUserDialog {
+	fn as_cstr(self) -> &'static CStr {
+		match self {
+			OverlayUserDialog::Profile => const_cstr!("steamid"),
+			OverlayUserDialog::Chat => const_cstr!("chat"),
+			OverlayUserDialog::JoinTrade => const_cstr!("jointrade"),
+			OverlayUserDialog::Stats => const_cstr!("stats"),
+			OverlayUserDialog::Achievements => const_cstr!("achievements"),
+			OverlayUserDialog::FriendAdd => const_cstr!("friendadd"),
+			OverlayUserDialog::FriendRemove => const_cstr!("friendremove"),
+			OverlayUserDialog::FriendRequestAccept => const_cstr!("friendrequestaccept"),
+			OverlayUserDialog::FriendRequestIgnore => const_cstr!("friendrequestignore"),
+		}
+		.as_cstr()
+	}
+}
+
+#[repr(u32)]
+pub enum OverlayWebPageMode {
+	Default,
+	/// Opens the page in a modal overlay window that closes with the page.
+	Modal,
+}
+
+#[repr(u32)]
+pub enum OverlayStoreFlag {
+	None,
+	AddToCart,
+	AddToCartAndShow,
+}
+
+/// Posted when the overlay opens or closes; games should pause while it is active.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GameOverlayActivated {
+	pub active:         bool,
+	pub user_initiated: bool,
+	pub appid:          u32,
+}
+
+declare_notification! {
+	Data (331) {
+		active:         u8,
+		user_initiated: bool,
+		_pad:           [u8; 2],
+		appid:          u32,
+		_overlay_pid:   u32,
+	} -> GameOverlayActivated;
+
+	map(|Data {active, user_initiated, appid, ..}| GameOverlayActivated {
+		active: active != 0,
+		user_initiated,
+		appid,
+	});
+}
+
+/// The maximum number of rich presence keys a user can have set.
+pub const RICH_PRESENCE_KEYS_MAX: usize = 30;
+/// The maximum length of a rich presence key, including the terminating NUL.
+pub const RICH_PRESENCE_KEY_LENGTH_MAX: usize = 64;
+/// The maximum length of a rich presence value, including the terminating NUL.
+pub const RICH_PRESENCE_VALUE_LENGTH_MAX: usize = 256;
+
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq)]
+pub enum RichPresenceError {
+	#[display(fmt = "Too many rich presence keys")]
+	TooManyKeys,
+	#[display(fmt = "Rich presence key is too long")]
+	KeyTooLong,
+	#[display(fmt = "Rich presence value is too long")]
+	ValueTooLong,
+	#[display(fmt = "Steam rejected the rich presence")]
+	Rejected,
+}
+
+impl std::error::Error for RichPresenceError {}
+
+/// Sets several rich presence keys at once, checking Steam's limits up front.
+///
+/// Only the keys set through the updater count towards `RICH_PRESENCE_KEYS_MAX`. Keys set
+/// earlier count as well on Steam's side, so `finish` can still fail with `Rejected` unless
+/// the rich presence was cleared first.
+pub struct RichPresenceUpdater<'a> {
+	friends: &'a Friends<'a>,
+	entries: Vec<(CString, CString)>,
+}
+
+impl<'a> RichPresenceUpdater<'a> {
+	/// Sets `key` to `value`; an empty value removes the key.
+	pub fn set(mut self, key: &CStr, value: &CStr) -> Result<Self, RichPresenceError> {
+		if key.to_bytes_with_nul().len() > RICH_PRESENCE_KEY_LENGTH_MAX {
+			return Err(RichPresenceError::KeyTooLong);
+		}
+		if value.to_bytes_with_nul().len() > RICH_PRESENCE_VALUE_LENGTH_MAX {
+			return Err(RichPresenceError::ValueTooLong);
+		}
+
+		match self.entries.iter().position(|(k, _)| k.as_c_str() == key) {
+			Some(i) => self.entries[i].1 = value.to_owned(),
+			None if self.entries.len() < RICH_PRESENCE_KEYS_MAX => {
+				self.entries.push((key.to_owned(), value.to_owned()))
+			},
+			None => return Err(RichPresenceError::TooManyKeys),
+		}
+		Ok(self)
+	}
+
+	/// Applies the keys in the order they were first set, stopping at the first one Steam
+	/// rejects; the keys before it stay applied.
+	pub fn finish(self) -> Result<(), RichPresenceError> {
+		for (key, value) in &self.entries {
+			if unsafe {
+				!SteamAPI_ISteamFriends_SetRichPresence(
+					self.friends.raw.clone(),
+					key.as_ptr(),
+					value.as_ptr(),
+				)
+			} {
+				return Err(RichPresenceError::Rejected);
+			}
+		}
+		Ok(())
+	}
+}
+
+interface!(Friends);
+impl<'a> Friends<'a> {
+	pub fn new(client: &Client<'a>) -> Option<Self> {
+		let raw = unsafe {
+			SteamAPI_ISteamClient_GetISteamFriends(
+				client.raw.clone(),
+				client.user(),
+				client.pipe(),
+				const_cstr!("SteamFriends017").as_ptr(),
+			)
+			.check()?
+		};
+		let utils = client.utils.clone();
+		let callbacks = client.callbacks.clone();
+
+		Some(Friends {
+			raw,
+			utils,
+			callbacks,
+		})
+	}
+
+	/// The current user's own persona name.
+	pub fn persona_name(&self) -> String {
+		string_from_ptr(unsafe { SteamAPI_ISteamFriends_GetPersonaName(self.raw.clone()) })
+			.unwrap_or_default()
+	}
+
+	pub fn friend_count(&self, flags: FriendFlags) -> u32 {
+		let count = unsafe { SteamAPI_ISteamFriends_GetFriendCount(self.raw.clone(), flags) };
+		count.max(0) as u32
+	}
+
+	pub fn friend_by_index(&self, index: u32, flags: FriendFlags) -> Option<SteamId> {
+		match unsafe {
+			SteamAPI_ISteamFriends_GetFriendByIndex(self.raw.clone(), index as i32, flags)
+		} {
+			SteamId(0) => None,
+			friend => Some(friend),
+		}
+	}
+
+	pub fn friends(&self, flags: FriendFlags) -> impl Iterator<Item = SteamId> + '_ {
+		(0..self.friend_count(flags)).filter_map(move |i| self.friend_by_index(i, flags))
+	}
+
+	pub fn friend_persona_state(&self, friend: SteamId) -> PersonaState {
+		unsafe { SteamAPI_ISteamFriends_GetFriendPersonaState(self.raw.clone(), friend) }.into()
+	}
+
+	pub fn friend_persona_name(&self, friend: SteamId) -> String {
+		string_from_ptr(unsafe {
+			SteamAPI_ISteamFriends_GetFriendPersonaName(self.raw.clone(), friend)
+		})
+		.unwrap_or_default()
+	}
+
+	pub fn persona_state_changes(&self) -> Notifications<'a, PersonaStateChange> {
+		self.callbacks.subscribe()
+	}
+
+	/// Requests the persona of a user who is not a friend.
+	///
+	/// Returns `false` if the information is already available,
+	/// otherwise it is announced through `persona_state_changes`.
+	pub fn request_user_information(&self, user: SteamId, name_only: bool) -> bool {
+		unsafe { SteamAPI_ISteamFriends_RequestUserInformation(self.raw.clone(), user, name_only) }
+	}
+
+	pub fn rich_presence(&'a self) -> RichPresenceUpdater<'a> {
+		RichPresenceUpdater {
+			friends: self,
+			entries: Vec::new(),
+		}
+	}
+
+	pub fn clear_rich_presence(&self) {
+		unsafe { SteamAPI_ISteamFriends_ClearRichPresence(self.raw.clone()) }
+	}
+
+	/// Requests the rich presence of a friend playing another game,
+	/// announced through `rich_presence_updates`.
+	pub fn request_friend_rich_presence(&self, friend: SteamId) {
+		unsafe { SteamAPI_ISteamFriends_RequestFriendRichPresence(self.raw.clone(), friend) }
+	}
+
+	pub fn friend_rich_presence(&self, friend: SteamId, key: &CStr) -> Option<String> {
+		string_from_ptr(unsafe {
+			SteamAPI_ISteamFriends_GetFriendRichPresence(self.raw.clone(), friend, key.as_ptr())
+		})
+		.filter(|value| !value.is_empty())
+	}
+
+	/// All the rich presence keys of a friend, with their values.
+	pub fn friend_rich_presence_entries(&self, friend: SteamId) -> Vec<(String, String)> {
+		let count = unsafe {
+			SteamAPI_ISteamFriends_GetFriendRichPresenceKeyCount(self.raw.clone(), friend)
+		};
+		(0..count)
+			.filter_map(|i| {
+				let raw_key = unsafe {
+					SteamAPI_ISteamFriends_GetFriendRichPresenceKeyByIndex(
+						self.raw.clone(),
+						friend,
+						i,
+					)
+				};
+				// The keys may have changed since they were counted.
+				let key = string_from_ptr(raw_key).filter(|key| !key.is_empty())?;
+				let value = unsafe {
+					SteamAPI_ISteamFriends_GetFriendRichPresence(self.raw.clone(), friend, raw_key)
+				};
+				Some((key, string_from_ptr(value)?))
+			})
+			.collect()
+	}
+
+	pub fn rich_presence_updates(&self) -> Notifications<'a, FriendRichPresenceUpdate> {
+		self.callbacks.subscribe()
+	}
+
+	pub fn activate_overlay(&self, dialog: OverlayDialog) {
+		unsafe {
+			SteamAPI_ISteamFriends_ActivateGameOverlay(self.raw.clone(), dialog.as_cstr().as_ptr())
+		}
+	}
+
+	pub fn activate_overlay_to_user(&self, dialog: OverlayUserDialog, user: SteamId) {
+		unsafe {
+			SteamAPI_ISteamFriends_ActivateGameOverlayToUser(
+				self.raw.clone(),
+				dialog.as_cstr().as_ptr(),
+				user,
+			)
+		}
+	}
+
+	pub fn activate_overlay_to_web_page(&self, url: &CStr, mode: OverlayWebPageMode) {
+		unsafe {
+			SteamAPI_ISteamFriends_ActivateGameOverlayToWebPage(
+				self.raw.clone(),
+				url.as_ptr(),
+				mode,
+			)
+		}
+	}
+
+	pub fn activate_overlay_to_store(&self, appid: u32, flag: OverlayStoreFlag) {
+		unsafe { SteamAPI_ISteamFriends_ActivateGameOverlayToStore(self.raw.clone(), appid, flag) }
+	}
+
+	pub fn overlay_activations(&self) -> Notifications<'a, GameOverlayActivated> {
+		self.callbacks.subscribe()
+	}
+
+	/// Fetches the avatar of a user, or `None` if it is not known;
+	/// `request_user_information` can be used to load it.
+	pub fn avatar(
+		&'a self,
+		user: SteamId,
+		size: AvatarSize,
+	) -> Option<impl Future<Item = Image, Error = Error> + 'a> {
+		let loaded = self.callbacks.subscribe::<AvatarImageLoaded>();
+		let image = unsafe {
+			match size {
+				AvatarSize::Small => {
+					SteamAPI_ISteamFriends_GetSmallFriendAvatar(self.raw.clone(), user)
+				},
+				AvatarSize::Medium => {
+					SteamAPI_ISteamFriends_GetMediumFriendAvatar(self.raw.clone(), user)
+				},
+				AvatarSize::Large => {
+					SteamAPI_ISteamFriends_GetLargeFriendAvatar(self.raw.clone(), user)
+				},
+			}
+		};
+
+		match image {
+			ImageHandle(0) => None,
+			ImageHandle(-1) => Some(Either::A(
+				loaded
+					.filter(move |loaded| loaded.user == user)
+					.into_future()
+					.map_err(|(e, _)| e)
+					.and_then(move |(loaded, _)| {
+						let loaded = loaded.ok_or(Error::Fail)?;
+						self.utils.image(loaded.image).ok_or(Error::Fail)
+					}),
+			)),
+			image => Some(Either::B(future::result(
+				self.utils.image(image).ok_or(Error::Fail),
+			))),
+		}
+	}
+}
+
+steam_extern! {
+	fn SteamAPI_ISteamClient_GetISteamFriends<'a>(a: Raw<Client<'a>>, b: User<'a>, c: Pipe<'a>, d: *const c_char) -> MaybeRaw<Friends<'a>>;
+
+	fn SteamAPI_ISteamFriends_GetPersonaName(a: Raw<Friends<'_>>) -> *const c_char;
+
+	fn SteamAPI_ISteamFriends_GetFriendCount(a: Raw<Friends<'_>>, b: FriendFlags)              -> i32;
+	fn SteamAPI_ISteamFriends_GetFriendByIndex(a: Raw<Friends<'_>>, b: i32, c: FriendFlags)    -> SteamId;
+	fn SteamAPI_ISteamFriends_GetFriendPersonaState(a: Raw<Friends<'_>>, b: SteamId)           -> u32;
+	fn SteamAPI_ISteamFriends_GetFriendPersonaName(a: Raw<Friends<'_>>, b: SteamId)            -> *const c_char;
+	fn SteamAPI_ISteamFriends_RequestUserInformation(a: Raw<Friends<'_>>, b: SteamId, c: bool) -> bool;
+
+	fn SteamAPI_ISteamFriends_SetRichPresence(a: Raw<Friends<'_>>, b: *const c_char, c: *const c_char) -> bool;
+	fn SteamAPI_ISteamFriends_ClearRichPresence(a: Raw<Friends<'_>>);
+	fn SteamAPI_ISteamFriends_RequestFriendRichPresence(a: Raw<Friends<'_>>, b: SteamId);
+	fn SteamAPI_ISteamFriends_GetFriendRichPresence(a: Raw<Friends<'_>>, b: SteamId, c: *const c_char) -> *const c_char;
+	fn SteamAPI_ISteamFriends_GetFriendRichPresenceKeyCount(a: Raw<Friends<'_>>, b: SteamId)           -> i32;
+	fn SteamAPI_ISteamFriends_GetFriendRichPresenceKeyByIndex(a: Raw<Friends<'_>>, b: SteamId, c: i32) -> *const c_char;
+
+	fn SteamAPI_ISteamFriends_ActivateGameOverlay(a: Raw<Friends<'_>>, b: *const c_char);
+	fn SteamAPI_ISteamFriends_ActivateGameOverlayToUser(a: Raw<Friends<'_>>, b: *const c_char, c: SteamId);
+	fn SteamAPI_ISteamFriends_ActivateGameOverlayToWebPage(a: Raw<Friends<'_>>, b: *const c_char, c: OverlayWebPageMode);
+	fn SteamAPI_ISteamFriends_ActivateGameOverlayToStore(a: Raw<Friends<'_>>, b: u32, c: OverlayStoreFlag);
+
+	fn SteamAPI_ISteamFriends_GetSmallFriendAvatar(a: Raw<Friends<'_>>, b: SteamId)  -> ImageHandle;
+	fn SteamAPI_ISteamFriends_GetMediumFriendAvatar(a: Raw<Friends<'_>>, b: SteamId) -> ImageHandle;
+	fn SteamAPI_ISteamFriends_GetLargeFriendAvatar(a: Raw<Friends<'_>>, b: SteamId)  -> ImageHandle;
+}
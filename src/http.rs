@@ -0,0 +1,521 @@
+use const_cstr::const_cstr;
+#[cfg(feature = "http")]
+use std::ffi::CString;
+use std::{ffi::CStr, mem::MaybeUninit, os::raw::c_char, time::Duration};
+
+use futures::{task, Async, Future, Poll, Stream};
+
+use crate::{APICall, Client, Error, MaybeAPICall, MaybeRaw, Notifications, Pipe, Raw, User};
+
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HttpMethod {
+	Get = 1,
+	Head,
+	Post,
+	Put,
+	Delete,
+	Options,
+	Patch,
+}
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct RequestHandle(u32);
+
+const INVALID_REQUEST: RequestHandle = RequestHandle(0);
+
+struct DataReceived {
+	handle: RequestHandle,
+	offset: u32,
+	len:    u32,
+}
+
+declare_notification! {
+	Data (2103) {
+		handle:   RequestHandle,
+		_context: u64,
+		offset:   u32,
+		len:      u32,
+	} -> DataReceived;
+
+	map(|Data {handle, offset, len, ..}| DataReceived {handle, offset, len});
+}
+
+/// A request made through Steam, released once dropped.
+pub struct HttpRequest<'a> {
+	http:   Http<'a>,
+	handle: RequestHandle,
+}
+
+impl Drop for HttpRequest<'_> {
+	fn drop(&mut self) {
+		unsafe { SteamAPI_ISteamHTTP_ReleaseHTTPRequest(self.http.raw.clone(), self.handle) };
+	}
+}
+
+impl<'a> HttpRequest<'a> {
+	pub fn set_header(&self, name: &CStr, value: &CStr) -> Result<(), ()> {
+		if unsafe {
+			SteamAPI_ISteamHTTP_SetHTTPRequestHeaderValue(
+				self.http.raw.clone(),
+				self.handle,
+				name.as_ptr(),
+				value.as_ptr(),
+			)
+		} {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	/// Sets a query parameter, or a form field for a `Post` without a raw body.
+	pub fn set_param(&self, name: &CStr, value: &CStr) -> Result<(), ()> {
+		if unsafe {
+			SteamAPI_ISteamHTTP_SetHTTPRequestGetOrPostParameter(
+				self.http.raw.clone(),
+				self.handle,
+				name.as_ptr(),
+				value.as_ptr(),
+			)
+		} {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	/// Sets the raw body of a `Post`, `Put` or `Patch`, replacing any form fields.
+	pub fn set_body(&self, content_type: &CStr, body: &[u8]) -> Result<(), ()> {
+		if unsafe {
+			SteamAPI_ISteamHTTP_SetHTTPRequestRawPostBody(
+				self.http.raw.clone(),
+				self.handle,
+				content_type.as_ptr(),
+				body.as_ptr(),
+				body.len() as u32,
+			)
+		} {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	/// How long the request may go without any network activity, 60 seconds by default.
+	pub fn set_activity_timeout(&self, timeout: Duration) -> Result<(), ()> {
+		if unsafe {
+			SteamAPI_ISteamHTTP_SetHTTPRequestNetworkActivityTimeout(
+				self.http.raw.clone(),
+				self.handle,
+				timeout.as_secs() as u32,
+			)
+		} {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	/// How long the whole request may take, regardless of network activity.
+	pub fn set_timeout(&self, timeout: Duration) -> Result<(), ()> {
+		if unsafe {
+			SteamAPI_ISteamHTTP_SetHTTPRequestAbsoluteTimeoutMS(
+				self.http.raw.clone(),
+				self.handle,
+				timeout.as_millis() as u32,
+			)
+		} {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	/// Appends `info` to Steam's user agent.
+	pub fn set_user_agent_info(&self, info: &CStr) -> Result<(), ()> {
+		if unsafe {
+			SteamAPI_ISteamHTTP_SetHTTPRequestUserAgentInfo(
+				self.http.raw.clone(),
+				self.handle,
+				info.as_ptr(),
+			)
+		} {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	pub fn set_requires_verified_certificate(&self, required: bool) -> Result<(), ()> {
+		if unsafe {
+			SteamAPI_ISteamHTTP_SetHTTPRequestRequiresVerifiedCertificate(
+				self.http.raw.clone(),
+				self.handle,
+				required,
+			)
+		} {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	/// Sends the request, resolving once the whole response has been received.
+	pub fn send(self) -> Option<impl Future<Item = HttpResponse<'a>, Error = Error> + 'a> {
+		let mut api_call = MaybeUninit::uninit();
+		if unsafe {
+			!SteamAPI_ISteamHTTP_SendHTTPRequest(
+				self.http.raw.clone(),
+				self.handle,
+				api_call.as_mut_ptr(),
+			)
+		} {
+			return None;
+		}
+
+		let completion = self.completion(unsafe { api_call.assume_init() })?;
+		Some(completion.map(move |status| HttpResponse {
+			request: self,
+			status,
+		}))
+	}
+
+	/// Sends the request, streaming the body of the response as it arrives.
+	pub fn send_streaming(self) -> Option<StreamingResponse<'a>> {
+		let data = self.http.callbacks.subscribe();
+		let mut api_call = MaybeUninit::uninit();
+		if unsafe {
+			!SteamAPI_ISteamHTTP_SendHTTPRequestAndStreamResponse(
+				self.http.raw.clone(),
+				self.handle,
+				api_call.as_mut_ptr(),
+			)
+		} {
+			return None;
+		}
+
+		let completion = self.completion(unsafe { api_call.assume_init() })?;
+		Some(StreamingResponse {
+			completion: Box::new(completion),
+			request: self,
+			data,
+			status: None,
+		})
+	}
+
+	fn completion(
+		&self,
+		api_call: MaybeAPICall,
+	) -> Option<impl Future<Item = u16, Error = Error> + 'a> {
+		declare_future! {
+			Data (2101) {
+				_handle:    RequestHandle,
+				_context:   u64,
+				successful: bool,
+				_pad:       [u8; 3],
+				status:     i32,
+				_body_size: u32,
+			} -> (bool, u16);
+
+			map(|Data {successful, status, ..}| Ok((successful, status as u16)));
+		}
+
+		let handle = Handle {
+			api_call: unsafe { APICall::new(api_call)? },
+			utils:    self.http.utils.clone(),
+		};
+		let http = self.http.clone();
+		let request = self.handle;
+		Some(handle.and_then(move |(successful, status)| {
+			if successful {
+				return Ok(status);
+			}
+
+			let mut timed_out = false;
+			unsafe {
+				SteamAPI_ISteamHTTP_GetHTTPRequestWasTimedOut(
+					http.raw.clone(),
+					request,
+					&mut timed_out,
+				)
+			};
+			Err(if timed_out {
+				Error::Timeout
+			} else {
+				Error::NoConnection
+			})
+		}))
+	}
+
+	fn header(&self, name: &CStr) -> Option<Vec<u8>> {
+		let mut len = 0;
+		if unsafe {
+			!SteamAPI_ISteamHTTP_GetHTTPResponseHeaderSize(
+				self.http.raw.clone(),
+				self.handle,
+				name.as_ptr(),
+				&mut len,
+			)
+		} {
+			return None;
+		}
+
+		let mut value = vec![0; len as usize];
+		if unsafe {
+			SteamAPI_ISteamHTTP_GetHTTPResponseHeaderValue(
+				self.http.raw.clone(),
+				self.handle,
+				name.as_ptr(),
+				value.as_mut_ptr(),
+				len,
+			)
+		} {
+			// The value is nul-terminated.
+			value.pop();
+			Some(value)
+		} else {
+			None
+		}
+	}
+}
+
+/// A response received in full.
+pub struct HttpResponse<'a> {
+	request: HttpRequest<'a>,
+	status:  u16,
+}
+
+impl<'a> HttpResponse<'a> {
+	pub fn status(&self) -> u16 {
+		self.status
+	}
+
+	/// The value of a header. Steam can't list the headers of a response, so they must be known.
+	pub fn header(&self, name: &CStr) -> Option<Vec<u8>> {
+		self.request.header(name)
+	}
+
+	pub fn body(&self) -> Option<Vec<u8>> {
+		let http = &self.request.http;
+		let mut len = 0;
+		if unsafe {
+			!SteamAPI_ISteamHTTP_GetHTTPResponseBodySize(
+				http.raw.clone(),
+				self.request.handle,
+				&mut len,
+			)
+		} {
+			return None;
+		}
+
+		let mut body = vec![0; len as usize];
+		if unsafe {
+			SteamAPI_ISteamHTTP_GetHTTPResponseBodyData(
+				http.raw.clone(),
+				self.request.handle,
+				body.as_mut_ptr(),
+				len,
+			)
+		} {
+			Some(body)
+		} else {
+			None
+		}
+	}
+}
+
+/// The body of a response, in chunks as they arrive.
+pub struct StreamingResponse<'a> {
+	request:    HttpRequest<'a>,
+	completion: Box<dyn Future<Item = u16, Error = Error> + 'a>,
+	data:       Notifications<'a, DataReceived>,
+	status:     Option<u16>,
+}
+
+impl StreamingResponse<'_> {
+	/// The status of the response, known once the whole body has been streamed.
+	pub fn status(&self) -> Option<u16> {
+		self.status
+	}
+
+	/// The value of a header, available once the first chunk has arrived.
+	pub fn header(&self, name: &CStr) -> Option<Vec<u8>> {
+		self.request.header(name)
+	}
+}
+
+impl Stream for StreamingResponse<'_> {
+	type Error = Error;
+	type Item = Vec<u8>;
+
+	fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+		if self.status.is_some() {
+			return Ok(Async::Ready(None));
+		}
+
+		while let Async::Ready(data) = self.data.poll()? {
+			let data = match data {
+				Some(data) if data.handle == self.request.handle => data,
+				Some(_) => continue,
+				None => return Ok(Async::Ready(None)),
+			};
+
+			let mut chunk = vec![0; data.len as usize];
+			if unsafe {
+				!SteamAPI_ISteamHTTP_GetHTTPStreamingResponseBodyData(
+					self.request.http.raw.clone(),
+					self.request.handle,
+					data.offset,
+					chunk.as_mut_ptr(),
+					data.len,
+				)
+			} {
+				return Err(Error::Fail);
+			}
+			return Ok(Async::Ready(Some(chunk)));
+		}
+
+		// Every chunk received so far has been taken, so the body is complete once the request is.
+		match self.completion.poll()? {
+			Async::Ready(status) => {
+				self.status = Some(status);
+				Ok(Async::Ready(None))
+			},
+			Async::NotReady => {
+				task::current().notify();
+				Ok(Async::NotReady)
+			},
+		}
+	}
+}
+
+interface!(Http);
+impl<'a> Http<'a> {
+	pub fn new(client: &Client<'a>) -> Option<Self> {
+		let raw = unsafe {
+			SteamAPI_ISteamClient_GetISteamHTTP(
+				client.raw.clone(),
+				client.user(),
+				client.pipe(),
+				const_cstr!("STEAMHTTP_INTERFACE_VERSION003").as_ptr(),
+			)
+			.check()?
+		};
+		let utils = client.utils.clone();
+		let callbacks = client.callbacks.clone();
+
+		Some(Http {
+			raw,
+			utils,
+			callbacks,
+		})
+	}
+
+	/// Creates a request to an absolute URL, to be sent once set up.
+	pub fn request(&self, method: HttpMethod, url: &CStr) -> Option<HttpRequest<'a>> {
+		let handle = unsafe {
+			SteamAPI_ISteamHTTP_CreateHTTPRequest(self.raw.clone(), method, url.as_ptr())
+		};
+		if handle == INVALID_REQUEST {
+			return None;
+		}
+
+		Some(HttpRequest {
+			http: self.clone(),
+			handle,
+		})
+	}
+}
+
+#[cfg(feature = "http")]
+impl<'a> Http<'a> {
+	/// Creates a request from an `http::Request`, if Steam supports its method, URL and headers.
+	pub fn request_from<B: AsRef<[u8]>>(
+		&self,
+		request: &::http::Request<B>,
+	) -> Option<HttpRequest<'a>> {
+		use ::http::{header::CONTENT_TYPE, Method};
+
+		let method = match *request.method() {
+			Method::GET => HttpMethod::Get,
+			Method::HEAD => HttpMethod::Head,
+			Method::POST => HttpMethod::Post,
+			Method::PUT => HttpMethod::Put,
+			Method::DELETE => HttpMethod::Delete,
+			Method::OPTIONS => HttpMethod::Options,
+			Method::PATCH => HttpMethod::Patch,
+			_ => return None,
+		};
+		let url = CString::new(request.uri().to_string()).ok()?;
+		let steam_request = self.request(method, &url)?;
+
+		for (name, value) in request.headers() {
+			// Steam sets it along with the body.
+			if *name == CONTENT_TYPE {
+				continue;
+			}
+			let name = CString::new(name.as_str()).ok()?;
+			let value = CString::new(value.as_bytes()).ok()?;
+			steam_request.set_header(&name, &value).ok()?;
+		}
+
+		let body = request.body().as_ref();
+		if !body.is_empty() {
+			let content_type: &[u8] = request
+				.headers()
+				.get(CONTENT_TYPE)
+				.map_or(b"application/octet-stream", |value| value.as_bytes());
+			let content_type = CString::new(content_type).ok()?;
+			steam_request.set_body(&content_type, body).ok()?;
+		}
+		Some(steam_request)
+	}
+}
+
+#[cfg(feature = "http")]
+impl HttpResponse<'_> {
+	/// Converts into an `http::Response`, with those of `headers` that the response has.
+	pub fn into_http(
+		self,
+		headers: &[::http::header::HeaderName],
+	) -> Result<::http::Response<Vec<u8>>, Error> {
+		let mut response = ::http::Response::builder();
+		response.status(self.status);
+		for name in headers {
+			let c_name = CString::new(name.as_str()).map_err(|_| Error::InvalidParam)?;
+			if let Some(value) = self.header(&c_name) {
+				response.header(name, value.as_slice());
+			}
+		}
+
+		let body = self.body().ok_or(Error::Fail)?;
+		response.body(body).map_err(|_| Error::Fail)
+	}
+}
+
+steam_extern! {
+	fn SteamAPI_ISteamClient_GetISteamHTTP<'a>(a: Raw<Client<'a>>, b: User<'a>, c: Pipe<'a>, d: *const c_char) -> MaybeRaw<Http<'a>>;
+
+	fn SteamAPI_ISteamHTTP_CreateHTTPRequest(a: Raw<Http<'_>>, b: HttpMethod, c: *const c_char) -> RequestHandle;
+	fn SteamAPI_ISteamHTTP_ReleaseHTTPRequest(a: Raw<Http<'_>>, b: RequestHandle)                 -> bool;
+
+	fn SteamAPI_ISteamHTTP_SetHTTPRequestHeaderValue(a: Raw<Http<'_>>, b: RequestHandle, c: *const c_char, d: *const c_char)          -> bool;
+	fn SteamAPI_ISteamHTTP_SetHTTPRequestGetOrPostParameter(a: Raw<Http<'_>>, b: RequestHandle, c: *const c_char, d: *const c_char)   -> bool;
+	fn SteamAPI_ISteamHTTP_SetHTTPRequestRawPostBody(a: Raw<Http<'_>>, b: RequestHandle, c: *const c_char, d: *const u8, e: u32)      -> bool;
+	fn SteamAPI_ISteamHTTP_SetHTTPRequestNetworkActivityTimeout(a: Raw<Http<'_>>, b: RequestHandle, c: u32)                           -> bool;
+	fn SteamAPI_ISteamHTTP_SetHTTPRequestAbsoluteTimeoutMS(a: Raw<Http<'_>>, b: RequestHandle, c: u32)                                -> bool;
+	fn SteamAPI_ISteamHTTP_SetHTTPRequestUserAgentInfo(a: Raw<Http<'_>>, b: RequestHandle, c: *const c_char)                          -> bool;
+	fn SteamAPI_ISteamHTTP_SetHTTPRequestRequiresVerifiedCertificate(a: Raw<Http<'_>>, b: RequestHandle, c: bool)                     -> bool;
+
+	fn SteamAPI_ISteamHTTP_SendHTTPRequest(a: Raw<Http<'_>>, b: RequestHandle, c: *mut MaybeAPICall)                  -> bool;
+	fn SteamAPI_ISteamHTTP_SendHTTPRequestAndStreamResponse(a: Raw<Http<'_>>, b: RequestHandle, c: *mut MaybeAPICall) -> bool;
+	fn SteamAPI_ISteamHTTP_GetHTTPRequestWasTimedOut(a: Raw<Http<'_>>, b: RequestHandle, c: *mut bool)                -> bool;
+
+	fn SteamAPI_ISteamHTTP_GetHTTPResponseHeaderSize(a: Raw<Http<'_>>, b: RequestHandle, c: *const c_char, d: *mut u32)             -> bool;
+	fn SteamAPI_ISteamHTTP_GetHTTPResponseHeaderValue(a: Raw<Http<'_>>, b: RequestHandle, c: *const c_char, d: *mut u8, e: u32)     -> bool;
+	fn SteamAPI_ISteamHTTP_GetHTTPResponseBodySize(a: Raw<Http<'_>>, b: RequestHandle, c: *mut u32)                                  -> bool;
+	fn SteamAPI_ISteamHTTP_GetHTTPResponseBodyData(a: Raw<Http<'_>>, b: RequestHandle, c: *mut u8, d: u32)                           -> bool;
+	fn SteamAPI_ISteamHTTP_GetHTTPStreamingResponseBodyData(a: Raw<Http<'_>>, b: RequestHandle, c: u32, d: *mut u8, e: u32)          -> bool;
+}
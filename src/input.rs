@@ -0,0 +1,525 @@
+use const_cstr::const_cstr;
+use derive_more::{Display, LowerHex, UpperHex};
+use std::{ffi::CStr, ops::BitOr, os::raw::c_char, path::PathBuf};
+
+use crate::{string_from_ptr, Client, MaybeRaw, Pipe, Raw, User};
+
+/// How many controllers, layers or origins Steam Input reports at most.
+const MAX_COUNT: usize = 16;
+
+/// A connected controller.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Hash, UpperHex, LowerHex)]
+pub struct InputHandle(pub u64);
+
+impl InputHandle {
+	/// Every connected controller at once, for the functions that act on controllers.
+	pub const ALL: InputHandle = InputHandle(u64::MAX);
+}
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Hash, UpperHex, LowerHex)]
+pub struct ActionSetHandle(pub u64);
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Hash, UpperHex, LowerHex)]
+pub struct DigitalActionHandle(pub u64);
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Hash, UpperHex, LowerHex)]
+pub struct AnalogActionHandle(pub u64);
+
+/// A physical button, stick or pad an action is bound to, as an `EInputActionOrigin`.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Hash)]
+pub struct ActionOrigin(pub i32);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InputType {
+	Unknown,
+	SteamController,
+	XBox360Controller,
+	XBoxOneController,
+	GenericGamepad,
+	PS4Controller,
+	AppleMFiController,
+	AndroidController,
+	SwitchJoyConPair,
+	SwitchJoyConSingle,
+	SwitchProController,
+	MobileTouch,
+	PS3Controller,
+	PS5Controller,
+	SteamDeckController,
+}
+
+impl From<i32> for InputType {
+	fn from(input_type: i32) -> Self {
+		match input_type {
+			1 => InputType::SteamController,
+			2 => InputType::XBox360Controller,
+			3 => InputType::XBoxOneController,
+			4 => InputType::GenericGamepad,
+			5 => InputType::PS4Controller,
+			6 => InputType::AppleMFiController,
+			7 => InputType::AndroidController,
+			8 => InputType::SwitchJoyConPair,
+			9 => InputType::SwitchJoyConSingle,
+			10 => InputType::SwitchProController,
+			11 => InputType::MobileTouch,
+			12 => InputType::PS3Controller,
+			13 => InputType::PS5Controller,
+			14 => InputType::SteamDeckController,
+			_ => InputType::Unknown,
+		}
+	}
+}
+
+/// How the input an analog action is bound to behaves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SourceMode {
+	None,
+	Dpad,
+	Buttons,
+	FourButtons,
+	AbsoluteMouse,
+	RelativeMouse,
+	JoystickMove,
+	JoystickMouse,
+	JoystickCamera,
+	ScrollWheel,
+	Trigger,
+	TouchMenu,
+	MouseJoystick,
+	MouseRegion,
+	RadialMenu,
+	SingleButton,
+	Switches,
+}
+
+impl From<i32> for SourceMode {
+	fn from(mode: i32) -> Self {
+		match mode {
+			1 => SourceMode::Dpad,
+			2 => SourceMode::Buttons,
+			3 => SourceMode::FourButtons,
+			4 => SourceMode::AbsoluteMouse,
+			5 => SourceMode::RelativeMouse,
+			6 => SourceMode::JoystickMove,
+			7 => SourceMode::JoystickMouse,
+			8 => SourceMode::JoystickCamera,
+			9 => SourceMode::ScrollWheel,
+			10 => SourceMode::Trigger,
+			11 => SourceMode::TouchMenu,
+			12 => SourceMode::MouseJoystick,
+			13 => SourceMode::MouseRegion,
+			14 => SourceMode::RadialMenu,
+			15 => SourceMode::SingleButton,
+			16 => SourceMode::Switches,
+			_ => SourceMode::None,
+		}
+	}
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DigitalActionData {
+	pub state:  bool,
+	/// Whether the action is bound in the active action set.
+	pub active: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AnalogActionData {
+	pub mode:   SourceMode,
+	pub x:      f32,
+	pub y:      f32,
+	/// Whether the action is bound in the active action set.
+	pub active: bool,
+}
+
+#[repr(C, packed)]
+#[derive(Clone, Copy)]
+struct RawAnalogActionData {
+	mode:   i32,
+	x:      f32,
+	y:      f32,
+	active: bool,
+}
+
+impl From<RawAnalogActionData> for AnalogActionData {
+	fn from(raw: RawAnalogActionData) -> Self {
+		AnalogActionData {
+			mode:   raw.mode.into(),
+			x:      raw.x,
+			y:      raw.y,
+			active: raw.active,
+		}
+	}
+}
+
+/// The size of a PNG glyph.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GlyphSize {
+	/// 32 by 32 pixels.
+	Small,
+	/// 128 by 128 pixels.
+	Medium,
+	/// 256 by 256 pixels.
+	Large,
+}
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct GlyphStyle(pub u32);
+
+impl GlyphStyle {
+	pub const DARK: GlyphStyle = GlyphStyle(0x2);
+	pub const KNOCKOUT: GlyphStyle = GlyphStyle(0x0);
+	pub const LIGHT: GlyphStyle = GlyphStyle(0x1);
+	/// Face buttons in a neutral color rather than the controller's own.
+	pub const NEUTRAL_COLOR_ABXY: GlyphStyle = GlyphStyle(0x10);
+	/// Face buttons with a solid fill.
+	pub const SOLID_ABXY: GlyphStyle = GlyphStyle(0x20);
+
+	pub fn contains(self, other: GlyphStyle) -> bool {
+		self.0 & other.0 == other.0
+	}
+}
+
+impl BitOr for GlyphStyle {
+	type Output = GlyphStyle;
+
+	fn bitor(self, other: GlyphStyle) -> GlyphStyle {
+		GlyphStyle(self.0 | other.0)
+	}
+}
+
+fn action_set(handle: ActionSetHandle) -> Option<ActionSetHandle> {
+	match handle {
+		ActionSetHandle(0) => None,
+		handle => Some(handle),
+	}
+}
+
+interface!(Input);
+impl<'a> Input<'a> {
+	pub fn new(client: &Client<'a>) -> Option<Self> {
+		let raw = unsafe {
+			SteamAPI_ISteamClient_GetISteamInput(
+				client.raw.clone(),
+				client.user(),
+				client.pipe(),
+				const_cstr!("SteamInput006").as_ptr(),
+			)
+			.check()?
+		};
+		let utils = client.utils.clone();
+		let callbacks = client.callbacks.clone();
+
+		Some(Input {
+			raw,
+			utils,
+			callbacks,
+		})
+	}
+
+	/// Starts Steam Input. With `explicitly_run_frame`, its state only updates on `run_frame`,
+	/// rather than with `Callbacks::run`.
+	pub fn init(&self, explicitly_run_frame: bool) -> Result<(), ()> {
+		if unsafe { SteamAPI_ISteamInput_Init(self.raw.clone(), explicitly_run_frame) } {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	pub fn shutdown(&self) -> Result<(), ()> {
+		if unsafe { SteamAPI_ISteamInput_Shutdown(self.raw.clone()) } {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	/// Updates the state of every controller.
+	pub fn run_frame(&self) {
+		unsafe { SteamAPI_ISteamInput_RunFrame(self.raw.clone(), false) }
+	}
+
+	pub fn connected_controllers(&self) -> Vec<InputHandle> {
+		let mut controllers = vec![InputHandle(0); MAX_COUNT];
+		let count = unsafe {
+			SteamAPI_ISteamInput_GetConnectedControllers(self.raw.clone(), controllers.as_mut_ptr())
+		};
+		controllers.truncate(count.max(0) as usize);
+		controllers
+	}
+
+	pub fn controller_type(&self, controller: InputHandle) -> InputType {
+		unsafe { SteamAPI_ISteamInput_GetInputTypeForHandle(self.raw.clone(), controller) }.into()
+	}
+
+	/// The controller emulating the XInput gamepad at `index`.
+	pub fn controller_for_gamepad_index(&self, index: u32) -> Option<InputHandle> {
+		match unsafe {
+			SteamAPI_ISteamInput_GetControllerForGamepadIndex(self.raw.clone(), index as i32)
+		} {
+			InputHandle(0) => None,
+			controller => Some(controller),
+		}
+	}
+
+	/// The index of the XInput gamepad a controller emulates, if it does.
+	pub fn gamepad_index(&self, controller: InputHandle) -> Option<u32> {
+		let index = unsafe {
+			SteamAPI_ISteamInput_GetGamepadIndexForController(self.raw.clone(), controller)
+		};
+		if index < 0 {
+			None
+		} else {
+			Some(index as u32)
+		}
+	}
+
+	/// Opens the overlay's binding screen for a controller.
+	pub fn show_binding_panel(&self, controller: InputHandle) -> Result<(), ()> {
+		if unsafe { SteamAPI_ISteamInput_ShowBindingPanel(self.raw.clone(), controller) } {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	/// Looks up an action set or layer by its name in the action manifest.
+	pub fn action_set(&self, name: &CStr) -> Option<ActionSetHandle> {
+		action_set(unsafe {
+			SteamAPI_ISteamInput_GetActionSetHandle(self.raw.clone(), name.as_ptr())
+		})
+	}
+
+	pub fn activate_action_set(&self, controller: InputHandle, set: ActionSetHandle) {
+		unsafe { SteamAPI_ISteamInput_ActivateActionSet(self.raw.clone(), controller, set) }
+	}
+
+	pub fn current_action_set(&self, controller: InputHandle) -> Option<ActionSetHandle> {
+		action_set(unsafe {
+			SteamAPI_ISteamInput_GetCurrentActionSet(self.raw.clone(), controller)
+		})
+	}
+
+	pub fn activate_action_set_layer(&self, controller: InputHandle, layer: ActionSetHandle) {
+		unsafe { SteamAPI_ISteamInput_ActivateActionSetLayer(self.raw.clone(), controller, layer) }
+	}
+
+	pub fn deactivate_action_set_layer(&self, controller: InputHandle, layer: ActionSetHandle) {
+		unsafe {
+			SteamAPI_ISteamInput_DeactivateActionSetLayer(self.raw.clone(), controller, layer)
+		}
+	}
+
+	pub fn deactivate_all_action_set_layers(&self, controller: InputHandle) {
+		unsafe { SteamAPI_ISteamInput_DeactivateAllActionSetLayers(self.raw.clone(), controller) }
+	}
+
+	pub fn active_action_set_layers(&self, controller: InputHandle) -> Vec<ActionSetHandle> {
+		let mut layers = vec![ActionSetHandle(0); MAX_COUNT];
+		let count = unsafe {
+			SteamAPI_ISteamInput_GetActiveActionSetLayers(
+				self.raw.clone(),
+				controller,
+				layers.as_mut_ptr(),
+			)
+		};
+		layers.truncate(count.max(0) as usize);
+		layers
+	}
+
+	pub fn digital_action(&self, name: &CStr) -> Option<DigitalActionHandle> {
+		match unsafe {
+			SteamAPI_ISteamInput_GetDigitalActionHandle(self.raw.clone(), name.as_ptr())
+		} {
+			DigitalActionHandle(0) => None,
+			action => Some(action),
+		}
+	}
+
+	pub fn digital_action_data(
+		&self,
+		controller: InputHandle,
+		action: DigitalActionHandle,
+	) -> DigitalActionData {
+		unsafe { SteamAPI_ISteamInput_GetDigitalActionData(self.raw.clone(), controller, action) }
+	}
+
+	/// What a digital action is bound to in an action set.
+	pub fn digital_action_origins(
+		&self,
+		controller: InputHandle,
+		set: ActionSetHandle,
+		action: DigitalActionHandle,
+	) -> Vec<ActionOrigin> {
+		let mut origins = vec![ActionOrigin(0); MAX_COUNT];
+		let count = unsafe {
+			SteamAPI_ISteamInput_GetDigitalActionOrigins(
+				self.raw.clone(),
+				controller,
+				set,
+				action,
+				origins.as_mut_ptr(),
+			)
+		};
+		origins.truncate(count.max(0) as usize);
+		origins
+	}
+
+	pub fn analog_action(&self, name: &CStr) -> Option<AnalogActionHandle> {
+		match unsafe { SteamAPI_ISteamInput_GetAnalogActionHandle(self.raw.clone(), name.as_ptr()) }
+		{
+			AnalogActionHandle(0) => None,
+			action => Some(action),
+		}
+	}
+
+	pub fn analog_action_data(
+		&self,
+		controller: InputHandle,
+		action: AnalogActionHandle,
+	) -> AnalogActionData {
+		unsafe { SteamAPI_ISteamInput_GetAnalogActionData(self.raw.clone(), controller, action) }
+			.into()
+	}
+
+	/// What an analog action is bound to in an action set.
+	pub fn analog_action_origins(
+		&self,
+		controller: InputHandle,
+		set: ActionSetHandle,
+		action: AnalogActionHandle,
+	) -> Vec<ActionOrigin> {
+		let mut origins = vec![ActionOrigin(0); MAX_COUNT];
+		let count = unsafe {
+			SteamAPI_ISteamInput_GetAnalogActionOrigins(
+				self.raw.clone(),
+				controller,
+				set,
+				action,
+				origins.as_mut_ptr(),
+			)
+		};
+		origins.truncate(count.max(0) as usize);
+		origins
+	}
+
+	/// Stops the momentum of a trackball-like analog action.
+	pub fn stop_analog_action_momentum(&self, controller: InputHandle, action: AnalogActionHandle) {
+		unsafe {
+			SteamAPI_ISteamInput_StopAnalogActionMomentum(self.raw.clone(), controller, action)
+		}
+	}
+
+	/// The local path of a PNG glyph for an origin.
+	pub fn glyph_png(
+		&self,
+		origin: ActionOrigin,
+		size: GlyphSize,
+		style: GlyphStyle,
+	) -> Option<PathBuf> {
+		string_from_ptr(unsafe {
+			SteamAPI_ISteamInput_GetGlyphPNGForActionOrigin(self.raw.clone(), origin, size, style)
+		})
+		.filter(|path| !path.is_empty())
+		.map(PathBuf::from)
+	}
+
+	/// The local path of an SVG glyph for an origin.
+	pub fn glyph_svg(&self, origin: ActionOrigin, style: GlyphStyle) -> Option<PathBuf> {
+		string_from_ptr(unsafe {
+			SteamAPI_ISteamInput_GetGlyphSVGForActionOrigin(self.raw.clone(), origin, style)
+		})
+		.filter(|path| !path.is_empty())
+		.map(PathBuf::from)
+	}
+
+	/// The localized name of an origin, e.g. "Left Trigger".
+	pub fn origin_name(&self, origin: ActionOrigin) -> String {
+		string_from_ptr(unsafe {
+			SteamAPI_ISteamInput_GetStringForActionOrigin(self.raw.clone(), origin)
+		})
+		.unwrap_or_default()
+	}
+
+	pub fn trigger_vibration(&self, controller: InputHandle, left: u16, right: u16) {
+		unsafe { SteamAPI_ISteamInput_TriggerVibration(self.raw.clone(), controller, left, right) }
+	}
+
+	/// Vibrates the motors behind the triggers as well, on controllers that have them.
+	pub fn trigger_vibration_extended(
+		&self,
+		controller: InputHandle,
+		left: u16,
+		right: u16,
+		left_trigger: u16,
+		right_trigger: u16,
+	) {
+		unsafe {
+			SteamAPI_ISteamInput_TriggerVibrationExtended(
+				self.raw.clone(),
+				controller,
+				left,
+				right,
+				left_trigger,
+				right_trigger,
+			)
+		}
+	}
+
+	pub fn set_led_color(&self, controller: InputHandle, r: u8, g: u8, b: u8) {
+		unsafe { SteamAPI_ISteamInput_SetLEDColor(self.raw.clone(), controller, r, g, b, 0) }
+	}
+
+	/// Restores the color the user chose for the LED.
+	pub fn reset_led_color(&self, controller: InputHandle) {
+		unsafe { SteamAPI_ISteamInput_SetLEDColor(self.raw.clone(), controller, 0, 0, 0, 1) }
+	}
+}
+
+steam_extern! {
+	fn SteamAPI_ISteamClient_GetISteamInput<'a>(a: Raw<Client<'a>>, b: User<'a>, c: Pipe<'a>, d: *const c_char) -> MaybeRaw<Input<'a>>;
+
+	fn SteamAPI_ISteamInput_Init(a: Raw<Input<'_>>, b: bool) -> bool;
+	fn SteamAPI_ISteamInput_Shutdown(a: Raw<Input<'_>>)     -> bool;
+	fn SteamAPI_ISteamInput_RunFrame(a: Raw<Input<'_>>, b: bool);
+
+	fn SteamAPI_ISteamInput_GetConnectedControllers(a: Raw<Input<'_>>, b: *mut InputHandle)    -> i32;
+	fn SteamAPI_ISteamInput_GetInputTypeForHandle(a: Raw<Input<'_>>, b: InputHandle)            -> i32;
+	fn SteamAPI_ISteamInput_GetControllerForGamepadIndex(a: Raw<Input<'_>>, b: i32)             -> InputHandle;
+	fn SteamAPI_ISteamInput_GetGamepadIndexForController(a: Raw<Input<'_>>, b: InputHandle)     -> i32;
+	fn SteamAPI_ISteamInput_ShowBindingPanel(a: Raw<Input<'_>>, b: InputHandle)                 -> bool;
+
+	fn SteamAPI_ISteamInput_GetActionSetHandle(a: Raw<Input<'_>>, b: *const c_char)                                  -> ActionSetHandle;
+	fn SteamAPI_ISteamInput_ActivateActionSet(a: Raw<Input<'_>>, b: InputHandle, c: ActionSetHandle);
+	fn SteamAPI_ISteamInput_GetCurrentActionSet(a: Raw<Input<'_>>, b: InputHandle)                                    -> ActionSetHandle;
+	fn SteamAPI_ISteamInput_ActivateActionSetLayer(a: Raw<Input<'_>>, b: InputHandle, c: ActionSetHandle);
+	fn SteamAPI_ISteamInput_DeactivateActionSetLayer(a: Raw<Input<'_>>, b: InputHandle, c: ActionSetHandle);
+	fn SteamAPI_ISteamInput_DeactivateAllActionSetLayers(a: Raw<Input<'_>>, b: InputHandle);
+	fn SteamAPI_ISteamInput_GetActiveActionSetLayers(a: Raw<Input<'_>>, b: InputHandle, c: *mut ActionSetHandle)      -> i32;
+
+	fn SteamAPI_ISteamInput_GetDigitalActionHandle(a: Raw<Input<'_>>, b: *const c_char)                                                                  -> DigitalActionHandle;
+	fn SteamAPI_ISteamInput_GetDigitalActionData(a: Raw<Input<'_>>, b: InputHandle, c: DigitalActionHandle)                                              -> DigitalActionData;
+	fn SteamAPI_ISteamInput_GetDigitalActionOrigins(a: Raw<Input<'_>>, b: InputHandle, c: ActionSetHandle, d: DigitalActionHandle, e: *mut ActionOrigin) -> i32;
+
+	fn SteamAPI_ISteamInput_GetAnalogActionHandle(a: Raw<Input<'_>>, b: *const c_char)                                                                 -> AnalogActionHandle;
+	fn SteamAPI_ISteamInput_GetAnalogActionData(a: Raw<Input<'_>>, b: InputHandle, c: AnalogActionHandle)                                               -> RawAnalogActionData;
+	fn SteamAPI_ISteamInput_GetAnalogActionOrigins(a: Raw<Input<'_>>, b: InputHandle, c: ActionSetHandle, d: AnalogActionHandle, e: *mut ActionOrigin) -> i32;
+	fn SteamAPI_ISteamInput_StopAnalogActionMomentum(a: Raw<Input<'_>>, b: InputHandle, c: AnalogActionHandle);
+
+	fn SteamAPI_ISteamInput_GetGlyphPNGForActionOrigin(a: Raw<Input<'_>>, b: ActionOrigin, c: GlyphSize, d: GlyphStyle) -> *const c_char;
+	fn SteamAPI_ISteamInput_GetGlyphSVGForActionOrigin(a: Raw<Input<'_>>, b: ActionOrigin, c: GlyphStyle)               -> *const c_char;
+	fn SteamAPI_ISteamInput_GetStringForActionOrigin(a: Raw<Input<'_>>, b: ActionOrigin)                                -> *const c_char;
+
+	fn SteamAPI_ISteamInput_TriggerVibration(a: Raw<Input<'_>>, b: InputHandle, c: u16, d: u16);
+	fn SteamAPI_ISteamInput_TriggerVibrationExtended(a: Raw<Input<'_>>, b: InputHandle, c: u16, d: u16, e: u16, f: u16);
+	fn SteamAPI_ISteamInput_SetLEDColor(a: Raw<Input<'_>>, b: InputHandle, c: u8, d: u8, e: u8, f: u32);
+}
@@ -32,9 +32,20 @@ macro_rules! interface {
 	($name:ident) => {
 		#[derive(Clone)]
 		pub struct $name<'a> {
-			pub(crate) raw:   crate::Raw<$name<'a>>,
-			pub(crate) utils: crate::Utils<'a>,
+			pub(crate) raw:       crate::Raw<$name<'a>>,
+			pub(crate) utils:     crate::Utils<'a>,
+			pub(crate) callbacks: crate::Callbacks<'a>,
 		}
 		impl crate::Interface for $name<'_> {}
+
+		impl<'a> $name<'a> {
+			pub fn utils(&self) -> &crate::Utils<'a> {
+				&self.utils
+			}
+
+			pub fn callbacks(&self) -> &crate::Callbacks<'a> {
+				&self.callbacks
+			}
+		}
 	};
 }
@@ -0,0 +1,577 @@
+use const_cstr::const_cstr;
+use derive_more::{Display, LowerHex, UpperHex};
+use std::{
+	ffi::CStr,
+	ops::BitOr,
+	os::raw::c_char,
+	ptr,
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use futures::{Async, Future, Poll, Stream};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	string_from_ptr,
+	APICall,
+	Client,
+	Error,
+	MaybeAPICall,
+	MaybeRaw,
+	Pipe,
+	Raw,
+	RawResult,
+	SteamId,
+	User,
+};
+
+/// An item instance in a user's inventory.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Hash, UpperHex, LowerHex)]
+pub struct ItemInstanceId(pub u64);
+
+/// An item definition, as configured for the app.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Hash, UpperHex, LowerHex)]
+pub struct ItemDef(pub i32);
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ItemFlags(pub u16);
+
+impl ItemFlags {
+	pub const CONSUMED: ItemFlags = ItemFlags(0x200);
+	pub const NONE: ItemFlags = ItemFlags(0x0);
+	pub const NO_TRADE: ItemFlags = ItemFlags(0x1);
+	pub const REMOVED: ItemFlags = ItemFlags(0x100);
+
+	pub fn contains(self, other: ItemFlags) -> bool {
+		self.0 & other.0 == other.0
+	}
+}
+
+impl BitOr for ItemFlags {
+	type Output = ItemFlags;
+
+	fn bitor(self, other: ItemFlags) -> ItemFlags {
+		ItemFlags(self.0 | other.0)
+	}
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ItemDetails {
+	pub id:         ItemInstanceId,
+	pub definition: ItemDef,
+	pub quantity:   u16,
+	pub flags:      ItemFlags,
+}
+
+/// The price of an item definition, in the smallest unit of the user's currency.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ItemPrice {
+	pub current: u64,
+	pub base:    u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Purchase {
+	pub order_id:       u64,
+	pub transaction_id: u64,
+}
+
+/// A result serialized by its owner, for a server to verify with `Inventory::deserialize`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+pub struct SerializedInventory(pub Vec<u8>);
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct ResultHandle(i32);
+
+const INVALID_RESULT: ResultHandle = ResultHandle(-1);
+
+struct ResultReady {
+	handle: ResultHandle,
+	result: Result<(), Error>,
+}
+
+declare_notification! {
+	Data (4700) {
+		handle: ResultHandle,
+		result: RawResult,
+	} -> ResultReady;
+
+	map(|Data {handle, result}| ResultReady {handle, result: result.into()});
+}
+
+struct DefinitionUpdate;
+
+declare_notification! {
+	Data (4702) {} -> DefinitionUpdate;
+
+	map(|Data {}| DefinitionUpdate);
+}
+
+/// The result of an inventory operation, destroyed when dropped.
+pub struct InventoryResult<'a> {
+	inventory: Inventory<'a>,
+	handle:    ResultHandle,
+}
+
+impl InventoryResult<'_> {
+	/// `Err(Error::Pending)` while the operation is still running.
+	pub fn status(&self) -> Result<(), Error> {
+		unsafe {
+			SteamAPI_ISteamInventory_GetResultStatus(self.inventory.raw.clone(), self.handle).into()
+		}
+	}
+
+	pub fn items(&self) -> Vec<ItemDetails> {
+		let mut count = 0;
+		if unsafe {
+			!SteamAPI_ISteamInventory_GetResultItems(
+				self.inventory.raw.clone(),
+				self.handle,
+				ptr::null_mut(),
+				&mut count,
+			)
+		} {
+			return Vec::new();
+		}
+
+		let mut items = Vec::with_capacity(count as usize);
+		if unsafe {
+			SteamAPI_ISteamInventory_GetResultItems(
+				self.inventory.raw.clone(),
+				self.handle,
+				items.as_mut_ptr(),
+				&mut count,
+			)
+		} {
+			unsafe { items.set_len((count as usize).min(items.capacity())) };
+		}
+		items
+	}
+
+	/// A property of the item at `index` in `items`, or all property names if `name` is `None`.
+	pub fn item_property(&self, index: u32, name: Option<&CStr>) -> Option<String> {
+		let name = name.map_or(ptr::null(), CStr::as_ptr);
+		read_property(|value, len| unsafe {
+			SteamAPI_ISteamInventory_GetResultItemProperty(
+				self.inventory.raw.clone(),
+				self.handle,
+				index,
+				name,
+				value,
+				len,
+			)
+		})
+	}
+
+	/// When the result was generated by the Steam servers.
+	pub fn timestamp(&self) -> SystemTime {
+		let secs = unsafe {
+			SteamAPI_ISteamInventory_GetResultTimestamp(self.inventory.raw.clone(), self.handle)
+		};
+		UNIX_EPOCH + Duration::from_secs(secs.into())
+	}
+
+	/// Whether the items of a deserialized result belong to `user`.
+	pub fn belongs_to(&self, user: SteamId) -> bool {
+		unsafe {
+			SteamAPI_ISteamInventory_CheckResultSteamID(
+				self.inventory.raw.clone(),
+				self.handle,
+				user,
+			)
+		}
+	}
+
+	/// Serializes the result, which must have come from `all_items` or `items_by_id`.
+	pub fn serialize(&self) -> Option<SerializedInventory> {
+		let mut len = 0;
+		if unsafe {
+			!SteamAPI_ISteamInventory_SerializeResult(
+				self.inventory.raw.clone(),
+				self.handle,
+				ptr::null_mut(),
+				&mut len,
+			)
+		} {
+			return None;
+		}
+
+		let mut bytes = vec![0; len as usize];
+		if unsafe {
+			SteamAPI_ISteamInventory_SerializeResult(
+				self.inventory.raw.clone(),
+				self.handle,
+				bytes.as_mut_ptr(),
+				&mut len,
+			)
+		} {
+			bytes.truncate(len as usize);
+			Some(SerializedInventory(bytes))
+		} else {
+			None
+		}
+	}
+}
+
+impl Drop for InventoryResult<'_> {
+	fn drop(&mut self) {
+		unsafe { SteamAPI_ISteamInventory_DestroyResult(self.inventory.raw.clone(), self.handle) }
+	}
+}
+
+/// Reads a string through `get`, asking for the required size first.
+fn read_property(get: impl Fn(*mut c_char, *mut u32) -> bool) -> Option<String> {
+	let mut len = 0;
+	if !get(ptr::null_mut(), &mut len) || len == 0 {
+		return None;
+	}
+
+	let mut value = vec![0 as c_char; len as usize];
+	if get(value.as_mut_ptr(), &mut len) {
+		string_from_ptr(value.as_ptr())
+	} else {
+		None
+	}
+}
+
+interface!(Inventory);
+impl<'a> Inventory<'a> {
+	pub fn new(client: &Client<'a>) -> Option<Self> {
+		let raw = unsafe {
+			SteamAPI_ISteamClient_GetISteamInventory(
+				client.raw.clone(),
+				client.user(),
+				client.pipe(),
+				const_cstr!("STEAMINVENTORY_INTERFACE_V003").as_ptr(),
+			)
+			.check()?
+		};
+		let utils = client.utils.clone();
+		let callbacks = client.callbacks.clone();
+
+		Some(Inventory {
+			raw,
+			utils,
+			callbacks,
+		})
+	}
+
+	/// Starts an operation through `start`, resolving once its result is ready.
+	fn result(
+		&self,
+		start: &mut dyn FnMut(*mut ResultHandle) -> bool,
+	) -> Option<impl Future<Item = InventoryResult<'a>, Error = Error> + 'a> {
+		let ready = self.callbacks.subscribe::<ResultReady>();
+		let mut handle = INVALID_RESULT;
+		if !start(&mut handle) || handle == INVALID_RESULT {
+			return None;
+		}
+
+		let result = InventoryResult {
+			inventory: self.clone(),
+			handle,
+		};
+		Some(
+			ready
+				.filter(move |ready| ready.handle == handle)
+				.into_future()
+				.map_err(|(e, _)| e)
+				.and_then(move |(ready, _)| {
+					ready.ok_or(Error::Fail)?.result?;
+					Ok(result)
+				}),
+		)
+	}
+
+	/// All the items of the current user.
+	pub fn all_items(&self) -> Option<impl Future<Item = InventoryResult<'a>, Error = Error> + 'a> {
+		self.result(&mut |handle| unsafe {
+			SteamAPI_ISteamInventory_GetAllItems(self.raw.clone(), handle)
+		})
+	}
+
+	/// Specific items of the current user, e.g. to show them to other players.
+	pub fn items_by_id(
+		&self,
+		ids: &[ItemInstanceId],
+	) -> Option<impl Future<Item = InventoryResult<'a>, Error = Error> + 'a> {
+		self.result(&mut |handle| unsafe {
+			SteamAPI_ISteamInventory_GetItemsByID(
+				self.raw.clone(),
+				handle,
+				ids.as_ptr(),
+				ids.len() as u32,
+			)
+		})
+	}
+
+	/// Verifies a result serialized by another user.
+	///
+	/// Fails with `Error::Expired` if it was serialized over an hour ago.
+	/// Check who it belongs to with `InventoryResult::belongs_to`.
+	pub fn deserialize(
+		&self,
+		serialized: &SerializedInventory,
+	) -> Option<impl Future<Item = InventoryResult<'a>, Error = Error> + 'a> {
+		let bytes = &serialized.0;
+		self.result(&mut |handle| unsafe {
+			SteamAPI_ISteamInventory_DeserializeResult(
+				self.raw.clone(),
+				handle,
+				bytes.as_ptr(),
+				bytes.len() as u32,
+				false,
+			)
+		})
+	}
+
+	pub fn consume_item(
+		&self,
+		id: ItemInstanceId,
+		quantity: u32,
+	) -> Option<impl Future<Item = InventoryResult<'a>, Error = Error> + 'a> {
+		self.result(&mut |handle| unsafe {
+			SteamAPI_ISteamInventory_ConsumeItem(self.raw.clone(), handle, id, quantity)
+		})
+	}
+
+	/// Crafts the `generate` items from the `destroy` items, following the exchange rules.
+	pub fn exchange_items(
+		&self,
+		generate: &[(ItemDef, u32)],
+		destroy: &[(ItemInstanceId, u32)],
+	) -> Option<impl Future<Item = InventoryResult<'a>, Error = Error> + 'a> {
+		let (generate, generate_quantities): (Vec<_>, Vec<_>) = generate.iter().cloned().unzip();
+		let (destroy, destroy_quantities): (Vec<_>, Vec<_>) = destroy.iter().cloned().unzip();
+		self.result(&mut |handle| unsafe {
+			SteamAPI_ISteamInventory_ExchangeItems(
+				self.raw.clone(),
+				handle,
+				generate.as_ptr(),
+				generate_quantities.as_ptr(),
+				generate.len() as u32,
+				destroy.as_ptr(),
+				destroy_quantities.as_ptr(),
+				destroy.len() as u32,
+			)
+		})
+	}
+
+	/// Moves `quantity` from one stack to another, or into a new stack if `to` is `None`.
+	pub fn transfer_item_quantity(
+		&self,
+		from: ItemInstanceId,
+		quantity: u32,
+		to: Option<ItemInstanceId>,
+	) -> Option<impl Future<Item = InventoryResult<'a>, Error = Error> + 'a> {
+		let to = to.unwrap_or(ItemInstanceId(u64::MAX));
+		self.result(&mut |handle| unsafe {
+			SteamAPI_ISteamInventory_TransferItemQuantity(
+				self.raw.clone(),
+				handle,
+				from,
+				quantity,
+				to,
+			)
+		})
+	}
+
+	/// Grants an item from a playtime drop list, if the user is due one.
+	pub fn trigger_item_drop(
+		&self,
+		drop_list: ItemDef,
+	) -> Option<impl Future<Item = InventoryResult<'a>, Error = Error> + 'a> {
+		self.result(&mut |handle| unsafe {
+			SteamAPI_ISteamInventory_TriggerItemDrop(self.raw.clone(), handle, drop_list)
+		})
+	}
+
+	/// Opens the overlay to buy `items`, resolving once the order is placed.
+	pub fn start_purchase(
+		&self,
+		items: &[(ItemDef, u32)],
+	) -> Option<impl Future<Item = Purchase, Error = Error> + 'a> {
+		declare_future! {
+			Data (4704) {
+				result:         RawResult,
+				order_id:       u64,
+				transaction_id: u64,
+			} -> Purchase;
+
+			map(|Data {result, order_id, transaction_id}| {
+				Result::from(result).map(|()| Purchase {order_id, transaction_id})
+			});
+		}
+
+		let (items, quantities): (Vec<_>, Vec<_>) = items.iter().cloned().unzip();
+		let api_call = unsafe {
+			SteamAPI_ISteamInventory_StartPurchase(
+				self.raw.clone(),
+				items.as_ptr(),
+				quantities.as_ptr(),
+				items.len() as u32,
+			)
+		};
+
+		Some(Handle {
+			api_call: unsafe { APICall::new(api_call)? },
+			utils:    self.utils.clone(),
+		})
+	}
+
+	/// Loads the item definitions, resolving once they are updated.
+	pub fn load_item_definitions(&self) -> Option<impl Future<Item = (), Error = Error> + 'a> {
+		let updates = self.callbacks.subscribe::<DefinitionUpdate>();
+		if unsafe { !SteamAPI_ISteamInventory_LoadItemDefinitions(self.raw.clone()) } {
+			return None;
+		}
+
+		Some(updates.into_future().map_err(|(e, _)| e).map(|_| ()))
+	}
+
+	pub fn item_definition_ids(&self) -> Vec<ItemDef> {
+		let mut count = 0;
+		if unsafe {
+			!SteamAPI_ISteamInventory_GetItemDefinitionIDs(
+				self.raw.clone(),
+				ptr::null_mut(),
+				&mut count,
+			)
+		} {
+			return Vec::new();
+		}
+
+		let mut ids = Vec::with_capacity(count as usize);
+		if unsafe {
+			SteamAPI_ISteamInventory_GetItemDefinitionIDs(
+				self.raw.clone(),
+				ids.as_mut_ptr(),
+				&mut count,
+			)
+		} {
+			unsafe { ids.set_len((count as usize).min(ids.capacity())) };
+		}
+		ids
+	}
+
+	/// A property of an item definition, or all property names if `name` is `None`.
+	pub fn item_definition_property(
+		&self,
+		definition: ItemDef,
+		name: Option<&CStr>,
+	) -> Option<String> {
+		let name = name.map_or(ptr::null(), CStr::as_ptr);
+		read_property(|value, len| unsafe {
+			SteamAPI_ISteamInventory_GetItemDefinitionProperty(
+				self.raw.clone(),
+				definition,
+				name,
+				value,
+				len,
+			)
+		})
+	}
+
+	/// Requests the prices of the item definitions, resolving to the user's currency code.
+	pub fn request_prices(&self) -> Option<impl Future<Item = String, Error = Error> + 'a> {
+		declare_future! {
+			Data (4705) {
+				result:   RawResult,
+				currency: [u8; 4],
+			} -> String;
+
+			map(|Data {result, currency}| {
+				Result::from(result)?;
+				let len = currency.iter().position(|&c| c == 0).unwrap_or(currency.len());
+				Ok(String::from_utf8_lossy(&currency[..len]).into_owned())
+			});
+		}
+
+		let api_call = unsafe { SteamAPI_ISteamInventory_RequestPrices(self.raw.clone()) };
+
+		Some(Handle {
+			api_call: unsafe { APICall::new(api_call)? },
+			utils:    self.utils.clone(),
+		})
+	}
+
+	pub fn item_price(&self, definition: ItemDef) -> Option<ItemPrice> {
+		let mut current = 0;
+		let mut base = 0;
+		if unsafe {
+			SteamAPI_ISteamInventory_GetItemPrice(
+				self.raw.clone(),
+				definition,
+				&mut current,
+				&mut base,
+			)
+		} {
+			Some(ItemPrice { current, base })
+		} else {
+			None
+		}
+	}
+
+	/// The prices of all item definitions that have one, once `request_prices` resolved.
+	pub fn items_with_prices(&self) -> Vec<(ItemDef, ItemPrice)> {
+		let count = unsafe { SteamAPI_ISteamInventory_GetNumItemsWithPrices(self.raw.clone()) };
+		let mut items = vec![ItemDef(0); count as usize];
+		let mut current = vec![0; count as usize];
+		let mut base = vec![0; count as usize];
+		if unsafe {
+			!SteamAPI_ISteamInventory_GetItemsWithPrices(
+				self.raw.clone(),
+				items.as_mut_ptr(),
+				current.as_mut_ptr(),
+				base.as_mut_ptr(),
+				count,
+			)
+		} {
+			return Vec::new();
+		}
+
+		items
+			.into_iter()
+			.zip(current.into_iter().zip(base))
+			.map(|(item, (current, base))| (item, ItemPrice { current, base }))
+			.collect()
+	}
+}
+
+steam_extern! {
+	fn SteamAPI_ISteamClient_GetISteamInventory<'a>(a: Raw<Client<'a>>, b: User<'a>, c: Pipe<'a>, d: *const c_char) -> MaybeRaw<Inventory<'a>>;
+
+	fn SteamAPI_ISteamInventory_GetResultStatus(a: Raw<Inventory<'_>>, b: ResultHandle)                                                       -> RawResult;
+	fn SteamAPI_ISteamInventory_GetResultItems(a: Raw<Inventory<'_>>, b: ResultHandle, c: *mut ItemDetails, d: *mut u32)                       -> bool;
+	fn SteamAPI_ISteamInventory_GetResultItemProperty(a: Raw<Inventory<'_>>, b: ResultHandle, c: u32, d: *const c_char, e: *mut c_char, f: *mut u32) -> bool;
+	fn SteamAPI_ISteamInventory_GetResultTimestamp(a: Raw<Inventory<'_>>, b: ResultHandle)                                                    -> u32;
+	fn SteamAPI_ISteamInventory_DestroyResult(a: Raw<Inventory<'_>>, b: ResultHandle);
+	fn SteamAPI_ISteamInventory_CheckResultSteamID(a: Raw<Inventory<'_>>, b: ResultHandle, c: SteamId) -> bool;
+
+	fn SteamAPI_ISteamInventory_SerializeResult(a: Raw<Inventory<'_>>, b: ResultHandle, c: *mut u8, d: *mut u32)                  -> bool;
+	fn SteamAPI_ISteamInventory_DeserializeResult(a: Raw<Inventory<'_>>, b: *mut ResultHandle, c: *const u8, d: u32, e: bool) -> bool;
+
+	fn SteamAPI_ISteamInventory_GetAllItems(a: Raw<Inventory<'_>>, b: *mut ResultHandle)                                                  -> bool;
+	fn SteamAPI_ISteamInventory_GetItemsByID(a: Raw<Inventory<'_>>, b: *mut ResultHandle, c: *const ItemInstanceId, d: u32)              -> bool;
+	fn SteamAPI_ISteamInventory_ConsumeItem(a: Raw<Inventory<'_>>, b: *mut ResultHandle, c: ItemInstanceId, d: u32)                      -> bool;
+	fn SteamAPI_ISteamInventory_ExchangeItems(a: Raw<Inventory<'_>>, b: *mut ResultHandle, c: *const ItemDef, d: *const u32, e: u32, f: *const ItemInstanceId, g: *const u32, h: u32) -> bool;
+	fn SteamAPI_ISteamInventory_TransferItemQuantity(a: Raw<Inventory<'_>>, b: *mut ResultHandle, c: ItemInstanceId, d: u32, e: ItemInstanceId) -> bool;
+	fn SteamAPI_ISteamInventory_TriggerItemDrop(a: Raw<Inventory<'_>>, b: *mut ResultHandle, c: ItemDef)                                 -> bool;
+	fn SteamAPI_ISteamInventory_StartPurchase(a: Raw<Inventory<'_>>, b: *const ItemDef, c: *const u32, d: u32)                          -> MaybeAPICall;
+
+	fn SteamAPI_ISteamInventory_LoadItemDefinitions(a: Raw<Inventory<'_>>) -> bool;
+	fn SteamAPI_ISteamInventory_GetItemDefinitionIDs(a: Raw<Inventory<'_>>, b: *mut ItemDef, c: *mut u32)                                    -> bool;
+	fn SteamAPI_ISteamInventory_GetItemDefinitionProperty(a: Raw<Inventory<'_>>, b: ItemDef, c: *const c_char, d: *mut c_char, e: *mut u32) -> bool;
+
+	fn SteamAPI_ISteamInventory_RequestPrices(a: Raw<Inventory<'_>>) -> MaybeAPICall;
+	fn SteamAPI_ISteamInventory_GetNumItemsWithPrices(a: Raw<Inventory<'_>>) -> u32;
+	fn SteamAPI_ISteamInventory_GetItemsWithPrices(a: Raw<Inventory<'_>>, b: *mut ItemDef, c: *mut u64, d: *mut u64, e: u32) -> bool;
+	fn SteamAPI_ISteamInventory_GetItemPrice(a: Raw<Inventory<'_>>, b: ItemDef, c: *mut u64, d: *mut u64)                  -> bool;
+}
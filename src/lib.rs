@@ -6,8 +6,48 @@ pub(crate) use self::interface::*;
 mod strings;
 pub(crate) use self::strings::*;
 
+mod app_ticket;
+pub use self::app_ticket::*;
+mod apps;
+pub use self::apps::*;
+mod callbacks;
+pub use self::callbacks::*;
+mod friends;
+pub use self::friends::*;
+mod http;
+pub use self::http::*;
+mod input;
+pub use self::input::*;
+mod inventory;
+pub use self::inventory::*;
+#[cfg(feature = "loopback")]
+mod loopback;
+#[cfg(feature = "loopback")]
+pub use self::loopback::*;
+mod matchmaking;
+pub use self::matchmaking::*;
+mod matchmaking_servers;
+pub use self::matchmaking_servers::*;
+mod micro_txn;
+pub use self::micro_txn::*;
+mod networking;
+pub use self::networking::*;
+mod networking_messages;
+pub use self::networking_messages::*;
+mod networking_sockets;
+pub use self::networking_sockets::*;
 mod remote_storage;
 pub use self::remote_storage::*;
+mod screenshots;
+pub use self::screenshots::*;
+mod stats_schema;
+pub use self::stats_schema::*;
+mod timeline;
+pub use self::timeline::*;
+mod user;
+pub use self::user::*;
+mod user_stats;
+pub use self::user_stats::*;
 mod utils;
 pub use self::utils::*;
 mod client;
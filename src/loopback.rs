@@ -0,0 +1,1345 @@
+//! An in-process stand-in for Steam networking, for testing netcode without Steam.
+//!
+//! Steam can't be faked behind `Client` and `Raw<T>`: a `Client` only comes from `SteamAPI_Init`,
+//! and `Raw<T>` is a pointer handed straight to the flat API. Netcode is instead written against
+//! `ConnectionTransport` and `MessageTransport`, which `NetworkingSockets`, `NetworkingMessages`
+//! and `LoopbackPeer` all implement.
+//!
+//! Peers reach each other by their fake `SteamId`s, or by the addresses they listen on with
+//! `listen_ip`. They have no addresses of their own, so the accepting side of a connection
+//! always sees the connecting peer's `SteamId`.
+//!
+//! A message sent on a connection is in flight until the receiving peer polls for it, or until
+//! the sender flushes it. Closing the connection with `linger` delivers those messages first,
+//! and closing it without drops them. Status changes and session failures are only seen by
+//! streams that exist when they happen.
+//!
+//! ```ignore
+//! let network = LoopbackNetwork::new();
+//! let host = network.peer(SteamId(1));
+//! let guest = network.peer(SteamId(2));
+//!
+//! let socket = host.listen_p2p(0).unwrap();
+//! let connection = guest.connect_p2p(&SteamId(1).into(), 0).unwrap();
+//! ```
+
+use std::{
+	cell::{RefCell, RefMut},
+	collections::{HashMap, HashSet, VecDeque},
+	ffi::CStr,
+	net::SocketAddr,
+	rc::{Rc, Weak},
+	time::Duration,
+};
+
+use futures::{future, stream, task, Async, Future, Stream};
+
+use crate::{
+	ConnectionInfo,
+	ConnectionState,
+	ConnectionStatus,
+	ConnectionStatusChanged,
+	ConnectionTransport,
+	Error,
+	ListenSocket,
+	MessageTransport,
+	NetConnection,
+	NetworkingIdentity,
+	NetworkingMessage,
+	PollGroup,
+	SendFlags,
+	SessionFailed,
+	SessionRequest,
+	SteamId,
+};
+
+type Queue<T> = Rc<RefCell<VecDeque<T>>>;
+
+/// The queues of a notification's subscribers, each of which only lives as long as its stream.
+struct Broadcast<T> {
+	subscribers: Vec<Weak<RefCell<VecDeque<T>>>>,
+}
+
+impl<T> Default for Broadcast<T> {
+	fn default() -> Self {
+		Broadcast {
+			subscribers: Vec::new(),
+		}
+	}
+}
+
+impl<T: Clone> Broadcast<T> {
+	fn subscribe(&mut self) -> Queue<T> {
+		let queue = Queue::default();
+		self.subscribers.push(Rc::downgrade(&queue));
+		queue
+	}
+
+	fn send(&mut self, item: T) {
+		self.subscribers.retain(|queue| queue.upgrade().is_some());
+		for queue in self.subscribers.iter().filter_map(Weak::upgrade) {
+			queue.borrow_mut().push_back(item.clone());
+		}
+	}
+}
+
+fn queue_stream<'a, T: 'a>(queue: Queue<T>) -> Box<dyn Stream<Item = T, Error = Error> + 'a> {
+	Box::new(stream::poll_fn(move || {
+		match queue.borrow_mut().pop_front() {
+			Some(item) => Ok(Async::Ready(Some(item))),
+			None => {
+				task::current().notify();
+				Ok(Async::NotReady)
+			},
+		}
+	}))
+}
+
+fn session_info(remote: SteamId, state: ConnectionState) -> ConnectionInfo {
+	ConnectionInfo {
+		remote: remote.into(),
+		user_data: 0,
+		listen_socket: None,
+		remote_addr: None,
+		state,
+		end_reason: 0,
+		end_debug: String::new(),
+		description: String::new(),
+	}
+}
+
+#[derive(Default)]
+struct Mailbox {
+	channels:         HashMap<i32, VecDeque<NetworkingMessage>>,
+	/// The channels used with each peer whose session is open.
+	sessions:         HashMap<SteamId, HashSet<i32>>,
+	/// Messages from peers whose session has not been accepted yet.
+	held:             HashMap<SteamId, Vec<NetworkingMessage>>,
+	session_requests: VecDeque<SteamId>,
+	session_failures: Broadcast<SessionFailed>,
+	status_changes:   Broadcast<ConnectionStatusChanged>,
+}
+
+/// Where a listen socket can be reached.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Endpoint {
+	P2P(SteamId, i32),
+	Ip(SocketAddr),
+}
+
+struct Connection {
+	owner:          SteamId,
+	remote:         NetworkingIdentity,
+	remote_addr:    Option<SocketAddr>,
+	peer:           Option<NetConnection>,
+	listen_socket:  Option<ListenSocket>,
+	poll_group:     Option<PollGroup>,
+	state:          ConnectionState,
+	user_data:      i64,
+	end_reason:     i32,
+	end_debug:      String,
+	/// Messages sent but not yet delivered to the peer, which an unlingered close drops.
+	outbox:         VecDeque<(u64, NetworkingMessage)>,
+	/// Delivered messages, numbered in the order they were sent on any connection.
+	inbox:          VecDeque<(u64, NetworkingMessage)>,
+	message_number: i64,
+}
+
+impl Connection {
+	fn new(owner: SteamId, remote: NetworkingIdentity) -> Self {
+		Connection {
+			owner,
+			remote,
+			remote_addr: None,
+			peer: None,
+			listen_socket: None,
+			poll_group: None,
+			state: ConnectionState::None,
+			user_data: 0,
+			end_reason: 0,
+			end_debug: String::new(),
+			outbox: VecDeque::new(),
+			inbox: VecDeque::new(),
+			message_number: 0,
+		}
+	}
+
+	fn info(&self) -> ConnectionInfo {
+		ConnectionInfo {
+			remote:        self.remote.clone(),
+			user_data:     self.user_data,
+			listen_socket: self.listen_socket,
+			remote_addr:   self.remote_addr,
+			state:         self.state,
+			end_reason:    self.end_reason,
+			end_debug:     self.end_debug.clone(),
+			description:   String::new(),
+		}
+	}
+
+	fn pop_message(&mut self) -> Option<NetworkingMessage> {
+		let (_, mut message) = self.inbox.pop_front()?;
+		message.connection_user_data = self.user_data;
+		Some(message)
+	}
+}
+
+#[derive(Default)]
+struct Network {
+	mailboxes:      HashMap<SteamId, Mailbox>,
+	listen_sockets: HashMap<ListenSocket, (SteamId, Endpoint)>,
+	requests:       HashMap<ListenSocket, VecDeque<NetConnection>>,
+	connections:    HashMap<NetConnection, Connection>,
+	poll_groups:    HashMap<PollGroup, SteamId>,
+	next_handle:    u32,
+	sent_messages:  u64,
+}
+
+impl Network {
+	fn next_handle(&mut self) -> u32 {
+		self.next_handle += 1;
+		self.next_handle
+	}
+
+	fn listener(&self, endpoint: Endpoint) -> Option<(ListenSocket, SteamId)> {
+		self.listen_sockets
+			.iter()
+			.find(|(_, &(_, listening))| listening == endpoint)
+			.map(|(&socket, &(owner, _))| (socket, owner))
+	}
+
+	/// The connection if `owner` has it.
+	fn connection(&mut self, owner: SteamId, connection: NetConnection) -> Option<&mut Connection> {
+		self.connections
+			.get_mut(&connection)
+			.filter(|c| c.owner == owner)
+	}
+
+	/// Sets the state of a connection, telling its owner's status change subscribers.
+	fn set_state(&mut self, connection: NetConnection, state: ConnectionState) {
+		let (owner, change) = match self.connections.get_mut(&connection) {
+			Some(c) => {
+				let old_state = c.state;
+				c.state = state;
+				(
+					c.owner,
+					ConnectionStatusChanged {
+						connection,
+						info: c.info(),
+						old_state,
+					},
+				)
+			},
+			None => return,
+		};
+		if let Some(mailbox) = self.mailboxes.get_mut(&owner) {
+			mailbox.status_changes.send(change);
+		}
+	}
+
+	/// Moves the messages in flight on `connection` to its peer.
+	fn deliver(&mut self, connection: NetConnection) {
+		let (peer, messages) = match self.connections.get_mut(&connection) {
+			Some(c) => (c.peer, c.outbox.drain(..).collect::<Vec<_>>()),
+			None => return,
+		};
+		if let Some(peer) = peer.and_then(|peer| self.connections.get_mut(&peer)) {
+			peer.inbox.extend(messages);
+		}
+	}
+
+	/// Delivers the messages in flight towards `connection`.
+	fn deliver_to(&mut self, connection: NetConnection) {
+		if let Some(peer) = self.connections.get(&connection).and_then(|c| c.peer) {
+			self.deliver(peer);
+		}
+	}
+
+	/// Closes a connection on behalf of its owner, telling the peer why.
+	fn close(&mut self, connection: NetConnection, reason: i32, debug: &str, linger: bool) {
+		if linger {
+			self.deliver(connection);
+		}
+		let peer = match self.connections.remove(&connection) {
+			Some(c) => c.peer,
+			None => return,
+		};
+		for requests in self.requests.values_mut() {
+			requests.retain(|&c| c != connection);
+		}
+
+		if let Some(peer) = peer {
+			if let Some(c) = self.connections.get_mut(&peer) {
+				c.peer = None;
+				c.outbox.clear();
+				c.end_reason = reason;
+				c.end_debug = debug.to_owned();
+			}
+			self.set_state(peer, ConnectionState::ClosedByPeer);
+		}
+	}
+
+	/// Ends the session `owner` has with `remote`, failing it for `remote`.
+	fn close_session(&mut self, owner: SteamId, remote: SteamId) -> bool {
+		let mailbox = self.mailboxes.entry(owner).or_default();
+		let open = mailbox.sessions.remove(&remote).is_some();
+		let held = mailbox.held.remove(&remote).is_some();
+		mailbox.session_requests.retain(|&r| r != remote);
+
+		if let Some(mailbox) = self.mailboxes.get_mut(&remote) {
+			if mailbox.sessions.remove(&owner).is_some() {
+				mailbox.session_failures.send(SessionFailed {
+					info: session_info(owner, ConnectionState::ClosedByPeer),
+				});
+			}
+		}
+		open || held
+	}
+}
+
+/// A set of peers that reach each other by their fake `SteamId`s.
+#[derive(Clone, Default)]
+pub struct LoopbackNetwork {
+	network: Rc<RefCell<Network>>,
+}
+
+impl LoopbackNetwork {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// A peer on this network, implementing both `ConnectionTransport` and `MessageTransport`.
+	pub fn peer(&self, steam_id: SteamId) -> LoopbackPeer {
+		self.network
+			.borrow_mut()
+			.mailboxes
+			.entry(steam_id)
+			.or_default();
+
+		LoopbackPeer {
+			network: self.clone(),
+			steam_id,
+		}
+	}
+}
+
+#[derive(Clone)]
+pub struct LoopbackPeer {
+	network:  LoopbackNetwork,
+	steam_id: SteamId,
+}
+
+impl LoopbackPeer {
+	pub fn steam_id(&self) -> SteamId {
+		self.steam_id
+	}
+
+	fn network(&self) -> RefMut<'_, Network> {
+		self.network.network.borrow_mut()
+	}
+
+	fn listen(&self, endpoint: Endpoint) -> Option<ListenSocket> {
+		let mut network = self.network();
+		if network.listener(endpoint).is_some() {
+			return None;
+		}
+
+		let socket = ListenSocket(network.next_handle());
+		network
+			.listen_sockets
+			.insert(socket, (self.steam_id, endpoint));
+		network.requests.insert(socket, VecDeque::new());
+		Some(socket)
+	}
+
+	fn connect(&self, remote: NetworkingIdentity, endpoint: Endpoint) -> NetConnection {
+		let mut network = self.network();
+		let local = NetConnection(network.next_handle());
+		let mut connection = Connection::new(self.steam_id, remote);
+		if let Endpoint::Ip(addr) = endpoint {
+			connection.remote_addr = Some(addr);
+		}
+
+		let (socket, owner) = match network.listener(endpoint) {
+			Some(listener) => listener,
+			None => {
+				network.connections.insert(local, connection);
+				network.set_state(local, ConnectionState::ProblemDetectedLocally);
+				return local;
+			},
+		};
+
+		let accepting = NetConnection(network.next_handle());
+		let mut accepted = Connection::new(owner, self.steam_id.into());
+		accepted.peer = Some(local);
+		accepted.listen_socket = Some(socket);
+		connection.peer = Some(accepting);
+		network.connections.insert(accepting, accepted);
+		network.connections.insert(local, connection);
+		network
+			.requests
+			.entry(socket)
+			.or_default()
+			.push_back(accepting);
+
+		network.set_state(local, ConnectionState::Connecting);
+		network.set_state(accepting, ConnectionState::Connecting);
+		local
+	}
+}
+
+impl ConnectionTransport for LoopbackPeer {
+	fn listen_p2p(&self, virtual_port: i32) -> Option<ListenSocket> {
+		self.listen(Endpoint::P2P(self.steam_id, virtual_port))
+	}
+
+	fn listen_ip(&self, addr: SocketAddr) -> Option<ListenSocket> {
+		self.listen(Endpoint::Ip(addr))
+	}
+
+	fn close_listen_socket(&self, socket: ListenSocket) -> Result<(), ()> {
+		let mut network = self.network();
+		match network.listen_sockets.get(&socket) {
+			Some(&(owner, _)) if owner == self.steam_id => {},
+			_ => return Err(()),
+		}
+
+		network.listen_sockets.remove(&socket);
+		network.requests.remove(&socket);
+		// As with Steam, this ungracefully closes the connections accepted on the socket too.
+		let accepted: Vec<_> = network
+			.connections
+			.iter()
+			.filter(|(_, c)| c.listen_socket == Some(socket))
+			.map(|(&connection, _)| connection)
+			.collect();
+		for connection in accepted {
+			network.close(connection, 0, "", false);
+		}
+		Ok(())
+	}
+
+	fn connect_p2p(
+		&self,
+		identity: &NetworkingIdentity,
+		virtual_port: i32,
+	) -> Option<NetConnection> {
+		match identity {
+			NetworkingIdentity::SteamId(id) => {
+				Some(self.connect(identity.clone(), Endpoint::P2P(*id, virtual_port)))
+			},
+			_ => None,
+		}
+	}
+
+	fn connect_ip(&self, addr: SocketAddr) -> Option<NetConnection> {
+		Some(self.connect(addr.into(), Endpoint::Ip(addr)))
+	}
+
+	fn connection_requests(
+		&self,
+		socket: ListenSocket,
+	) -> Box<dyn Stream<Item = NetConnection, Error = Error> + '_> {
+		Box::new(stream::poll_fn(move || {
+			let mut network = self.network();
+			match network.requests.get_mut(&socket) {
+				None => Ok(Async::Ready(None)),
+				Some(requests) => match requests.pop_front() {
+					Some(connection) => Ok(Async::Ready(Some(connection))),
+					None => {
+						task::current().notify();
+						Ok(Async::NotReady)
+					},
+				},
+			}
+		}))
+	}
+
+	fn accept_connection(&self, connection: NetConnection) -> Result<(), Error> {
+		let mut network = self.network();
+		let peer = match network.connection(self.steam_id, connection) {
+			Some(c) if c.listen_socket.is_some() => {
+				if c.state != ConnectionState::Connecting {
+					return Err(Error::InvalidState);
+				}
+				c.peer
+			},
+			_ => return Err(Error::InvalidParam),
+		};
+
+		network.set_state(connection, ConnectionState::Connected);
+		if let Some(peer) = peer {
+			network.set_state(peer, ConnectionState::Connected);
+		}
+		Ok(())
+	}
+
+	fn close_connection(
+		&self,
+		connection: NetConnection,
+		reason: i32,
+		debug: Option<&CStr>,
+		linger: bool,
+	) -> Result<(), ()> {
+		let mut network = self.network();
+		network.connection(self.steam_id, connection).ok_or(())?;
+
+		let debug = debug.map_or_else(String::new, |debug| debug.to_string_lossy().into_owned());
+		network.close(connection, reason, &debug, linger);
+		Ok(())
+	}
+
+	fn set_connection_user_data(&self, connection: NetConnection, data: i64) -> Result<(), ()> {
+		let mut network = self.network();
+		network
+			.connection(self.steam_id, connection)
+			.ok_or(())?
+			.user_data = data;
+		Ok(())
+	}
+
+	fn connection_info(&self, connection: NetConnection) -> Option<ConnectionInfo> {
+		let mut network = self.network();
+		network
+			.connection(self.steam_id, connection)
+			.map(|c| c.info())
+	}
+
+	fn connection_status(&self, connection: NetConnection) -> Result<ConnectionStatus, Error> {
+		let mut network = self.network();
+		let c = network
+			.connection(self.steam_id, connection)
+			.ok_or(Error::NoConnection)?;
+
+		let reliable = c
+			.outbox
+			.iter()
+			.filter(|(_, m)| m.flags.contains(SendFlags::RELIABLE))
+			.count() as u32;
+		Ok(ConnectionStatus {
+			state:                 c.state,
+			ping:                  Duration::from_secs(0),
+			quality_local:         1.0,
+			quality_remote:        1.0,
+			out_packets_per_sec:   0.0,
+			out_bytes_per_sec:     0.0,
+			in_packets_per_sec:    0.0,
+			in_bytes_per_sec:      0.0,
+			send_rate:             0,
+			pending_unreliable:    c.outbox.len() as u32 - reliable,
+			pending_reliable:      reliable,
+			sent_unacked_reliable: 0,
+			queue_time:            Duration::from_secs(0),
+		})
+	}
+
+	fn connection_status_changes(
+		&self,
+	) -> Box<dyn Stream<Item = ConnectionStatusChanged, Error = Error> + '_> {
+		let mut network = self.network();
+		let mailbox = network.mailboxes.entry(self.steam_id).or_default();
+		queue_stream(mailbox.status_changes.subscribe())
+	}
+
+	fn connected(
+		&self,
+		connection: NetConnection,
+	) -> Box<dyn Future<Item = (), Error = Error> + '_> {
+		Box::new(future::poll_fn(move || {
+			match self.connection_state(connection) {
+				ConnectionState::Connected => Ok(Async::Ready(())),
+				ConnectionState::Connecting | ConnectionState::FindingRoute => {
+					task::current().notify();
+					Ok(Async::NotReady)
+				},
+				_ => Err(Error::NoConnection),
+			}
+		}))
+	}
+
+	fn send_message(
+		&self,
+		connection: NetConnection,
+		data: &[u8],
+		flags: SendFlags,
+	) -> Result<i64, Error> {
+		let mut network = self.network();
+		let (peer, message_number) = match network.connection(self.steam_id, connection) {
+			Some(c) => {
+				if c.state != ConnectionState::Connected {
+					return Err(Error::NoConnection);
+				}
+				c.message_number += 1;
+				(c.peer.ok_or(Error::NoConnection)?, c.message_number)
+			},
+			None => return Err(Error::InvalidParam),
+		};
+
+		let message = NetworkingMessage {
+			data: data.to_vec(),
+			connection: peer,
+			peer: self.steam_id.into(),
+			connection_user_data: 0,
+			message_number,
+			channel: 0,
+			flags,
+		};
+		network.sent_messages += 1;
+		let sequence = network.sent_messages;
+		if let Some(c) = network.connections.get_mut(&connection) {
+			c.outbox.push_back((sequence, message));
+		}
+		Ok(message_number)
+	}
+
+	fn flush_messages(&self, connection: NetConnection) -> Result<(), Error> {
+		let mut network = self.network();
+		network
+			.connection(self.steam_id, connection)
+			.ok_or(Error::InvalidParam)?;
+		network.deliver(connection);
+		Ok(())
+	}
+
+	fn receive_messages(&self, connection: NetConnection, max: usize) -> Vec<NetworkingMessage> {
+		let mut network = self.network();
+		if network.connection(self.steam_id, connection).is_none() {
+			return Vec::new();
+		}
+		network.deliver_to(connection);
+
+		let c = network.connections.get_mut(&connection).unwrap();
+		let count = max.min(c.inbox.len());
+		(0..count).filter_map(|_| c.pop_message()).collect()
+	}
+
+	fn create_poll_group(&self) -> Option<PollGroup> {
+		let mut network = self.network();
+		let group = PollGroup(network.next_handle());
+		network.poll_groups.insert(group, self.steam_id);
+		Some(group)
+	}
+
+	fn destroy_poll_group(&self, group: PollGroup) -> Result<(), ()> {
+		let mut network = self.network();
+		if network.poll_groups.get(&group) != Some(&self.steam_id) {
+			return Err(());
+		}
+
+		network.poll_groups.remove(&group);
+		for c in network.connections.values_mut() {
+			if c.poll_group == Some(group) {
+				c.poll_group = None;
+			}
+		}
+		Ok(())
+	}
+
+	fn set_connection_poll_group(
+		&self,
+		connection: NetConnection,
+		group: Option<PollGroup>,
+	) -> Result<(), ()> {
+		let mut network = self.network();
+		if let Some(group) = group {
+			if network.poll_groups.get(&group) != Some(&self.steam_id) {
+				return Err(());
+			}
+		}
+
+		network
+			.connection(self.steam_id, connection)
+			.ok_or(())?
+			.poll_group = group;
+		Ok(())
+	}
+
+	fn receive_messages_on_poll_group(
+		&self,
+		group: PollGroup,
+		max: usize,
+	) -> Vec<NetworkingMessage> {
+		let mut network = self.network();
+		if network.poll_groups.get(&group) != Some(&self.steam_id) {
+			return Vec::new();
+		}
+
+		let members: Vec<_> = network
+			.connections
+			.iter()
+			.filter(|(_, c)| c.poll_group == Some(group))
+			.map(|(&connection, _)| connection)
+			.collect();
+		for &connection in &members {
+			network.deliver_to(connection);
+		}
+
+		let mut messages = Vec::new();
+		while messages.len() < max {
+			let next = members
+				.iter()
+				.filter_map(|connection| {
+					let (sequence, _) = network.connections[connection].inbox.front()?;
+					Some((*sequence, *connection))
+				})
+				.min_by_key(|&(sequence, _)| sequence);
+			let connection = match next {
+				Some((_, connection)) => connection,
+				None => break,
+			};
+			messages.extend(
+				network
+					.connections
+					.get_mut(&connection)
+					.and_then(Connection::pop_message),
+			);
+		}
+		messages
+	}
+}
+
+impl MessageTransport for LoopbackPeer {
+	fn send_message(
+		&self,
+		remote: &NetworkingIdentity,
+		channel: i32,
+		data: &[u8],
+		flags: SendFlags,
+	) -> Result<(), Error> {
+		let remote = match remote {
+			NetworkingIdentity::SteamId(id) => *id,
+			_ => return Err(Error::InvalidParam),
+		};
+
+		let mut network = self.network();
+		let me = self.steam_id;
+		let mailbox = network
+			.mailboxes
+			.get_mut(&remote)
+			.ok_or(Error::NoConnection)?;
+		let message = NetworkingMessage {
+			data: data.to_vec(),
+			connection: NetConnection(0),
+			peer: me.into(),
+			connection_user_data: 0,
+			message_number: 0,
+			channel,
+			flags,
+		};
+		match mailbox.sessions.get_mut(&me) {
+			Some(channels) => {
+				channels.insert(channel);
+				mailbox
+					.channels
+					.entry(channel)
+					.or_default()
+					.push_back(message);
+			},
+			None => {
+				if !mailbox.held.contains_key(&me) {
+					mailbox.session_requests.push_back(me);
+				}
+				mailbox.held.entry(me).or_default().push(message);
+			},
+		}
+
+		// Sending to a peer accepts its session, as with Steam.
+		network
+			.mailboxes
+			.entry(me)
+			.or_default()
+			.sessions
+			.entry(remote)
+			.or_default()
+			.insert(channel);
+		Ok(())
+	}
+
+	fn receive_messages(&self, channel: i32, max: usize) -> Vec<NetworkingMessage> {
+		let mut network = self.network();
+		let mailbox = network.mailboxes.entry(self.steam_id).or_default();
+		match mailbox.channels.get_mut(&channel) {
+			Some(messages) => {
+				let count = max.min(messages.len());
+				messages.drain(..count).collect()
+			},
+			None => Vec::new(),
+		}
+	}
+
+	fn channel_messages(
+		&self,
+		channel: i32,
+	) -> Box<dyn Stream<Item = NetworkingMessage, Error = Error> + '_> {
+		Box::new(stream::poll_fn(
+			move || match MessageTransport::receive_messages(self, channel, 1).pop() {
+				Some(message) => Ok(Async::Ready(Some(message))),
+				None => {
+					task::current().notify();
+					Ok(Async::NotReady)
+				},
+			},
+		))
+	}
+
+	fn session_requests(&self) -> Box<dyn Stream<Item = SessionRequest, Error = Error> + '_> {
+		Box::new(stream::poll_fn(move || {
+			let mut network = self.network();
+			let mailbox = network.mailboxes.entry(self.steam_id).or_default();
+			match mailbox.session_requests.pop_front() {
+				Some(remote) => Ok(Async::Ready(Some(SessionRequest {
+					remote: remote.into(),
+				}))),
+				None => {
+					task::current().notify();
+					Ok(Async::NotReady)
+				},
+			}
+		}))
+	}
+
+	fn session_failures(&self) -> Box<dyn Stream<Item = SessionFailed, Error = Error> + '_> {
+		let mut network = self.network();
+		let mailbox = network.mailboxes.entry(self.steam_id).or_default();
+		queue_stream(mailbox.session_failures.subscribe())
+	}
+
+	fn accept_session(&self, remote: &NetworkingIdentity) -> Result<(), ()> {
+		let remote = match remote {
+			NetworkingIdentity::SteamId(id) => *id,
+			_ => return Err(()),
+		};
+
+		let mut network = self.network();
+		let mailbox = network.mailboxes.entry(self.steam_id).or_default();
+		let held = mailbox.held.remove(&remote).ok_or(())?;
+		let channels = mailbox.sessions.entry(remote).or_default();
+		channels.extend(held.iter().map(|message| message.channel));
+		for message in held {
+			mailbox
+				.channels
+				.entry(message.channel)
+				.or_default()
+				.push_back(message);
+		}
+		Ok(())
+	}
+
+	fn close_session(&self, remote: &NetworkingIdentity) -> Result<(), ()> {
+		let remote = match remote {
+			NetworkingIdentity::SteamId(id) => *id,
+			_ => return Err(()),
+		};
+
+		if self.network().close_session(self.steam_id, remote) {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	fn close_channel(&self, remote: &NetworkingIdentity, channel: i32) -> Result<(), ()> {
+		let remote_id = match remote {
+			NetworkingIdentity::SteamId(id) => *id,
+			_ => return Err(()),
+		};
+
+		let mut network = self.network();
+		let mailbox = network.mailboxes.entry(self.steam_id).or_default();
+		let channels = mailbox.sessions.get_mut(&remote_id).ok_or(())?;
+		if !channels.remove(&channel) {
+			return Err(());
+		}
+		let last = channels.is_empty();
+		if let Some(messages) = mailbox.channels.get_mut(&channel) {
+			messages.retain(|message| message.peer != *remote);
+		}
+
+		if last {
+			network.close_session(self.steam_id, remote_id);
+		}
+		Ok(())
+	}
+
+	fn session_info(
+		&self,
+		remote: &NetworkingIdentity,
+	) -> (ConnectionState, Option<ConnectionInfo>) {
+		let remote = match remote {
+			NetworkingIdentity::SteamId(id) => *id,
+			_ => return (ConnectionState::None, None),
+		};
+
+		let mut network = self.network();
+		let accepted = match network.mailboxes.get(&remote) {
+			Some(mailbox) => mailbox.sessions.contains_key(&self.steam_id),
+			None => false,
+		};
+		let mailbox = network.mailboxes.entry(self.steam_id).or_default();
+		let state = if mailbox.held.contains_key(&remote) {
+			ConnectionState::Connecting
+		} else if mailbox.sessions.contains_key(&remote) {
+			if accepted {
+				ConnectionState::Connected
+			} else {
+				ConnectionState::Connecting
+			}
+		} else {
+			return (ConnectionState::None, None);
+		};
+		(state, Some(session_info(remote, state)))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use futures::{executor, Stream};
+
+	use super::*;
+
+	fn next<S: Stream>(stream: S) -> Option<S::Item>
+	where
+		S::Error: std::fmt::Debug,
+	{
+		executor::spawn(stream.take(1))
+			.wait_stream()
+			.map(Result::unwrap)
+	}
+
+	fn take<S: Stream>(stream: S, count: u64) -> Vec<S::Item>
+	where
+		S::Error: std::fmt::Debug,
+	{
+		executor::spawn(stream.take(count).collect())
+			.wait_future()
+			.unwrap()
+	}
+
+	fn data(messages: &[NetworkingMessage]) -> Vec<&[u8]> {
+		messages.iter().map(|m| &m.data[..]).collect()
+	}
+
+	fn connected(host: &LoopbackPeer, guest: &LoopbackPeer) -> (NetConnection, NetConnection) {
+		let socket = host.listen_p2p(0).unwrap();
+		let outgoing = guest.connect_p2p(&host.steam_id().into(), 0).unwrap();
+		let incoming = next(host.connection_requests(socket)).unwrap();
+		host.accept_connection(incoming).unwrap();
+		(incoming, outgoing)
+	}
+
+	#[test]
+	fn connect_and_accept() {
+		let network = LoopbackNetwork::new();
+		let host = network.peer(SteamId(1));
+		let guest = network.peer(SteamId(2));
+
+		let socket = host.listen_p2p(7).unwrap();
+		assert_eq!(host.listen_p2p(7), None);
+		assert!(guest.listen_p2p(7).is_some());
+
+		let outgoing = guest.connect_p2p(&SteamId(1).into(), 7).unwrap();
+		assert_eq!(
+			guest.connection_state(outgoing),
+			ConnectionState::Connecting
+		);
+
+		let incoming = next(host.connection_requests(socket)).unwrap();
+		assert_eq!(host.connection_state(incoming), ConnectionState::Connecting);
+		assert_eq!(guest.accept_connection(outgoing), Err(Error::InvalidParam));
+
+		host.accept_connection(incoming).unwrap();
+		assert_eq!(host.connection_state(incoming), ConnectionState::Connected);
+		assert_eq!(guest.connection_state(outgoing), ConnectionState::Connected);
+		assert_eq!(host.accept_connection(incoming), Err(Error::InvalidState));
+	}
+
+	#[test]
+	fn connect_without_listener() {
+		let network = LoopbackNetwork::new();
+		let guest = network.peer(SteamId(2));
+		network.peer(SteamId(1));
+
+		let connection = guest.connect_p2p(&SteamId(1).into(), 0).unwrap();
+		assert_eq!(
+			guest.connection_state(connection),
+			ConnectionState::ProblemDetectedLocally
+		);
+		assert_eq!(
+			ConnectionTransport::send_message(&guest, connection, b"hi", SendFlags::RELIABLE),
+			Err(Error::NoConnection)
+		);
+	}
+
+	#[test]
+	fn messages_arrive_in_order() {
+		let network = LoopbackNetwork::new();
+		let host = network.peer(SteamId(1));
+		let guest = network.peer(SteamId(2));
+		let (incoming, outgoing) = connected(&host, &guest);
+
+		for data in &[&b"one"[..], b"two", b"three"] {
+			ConnectionTransport::send_message(&guest, outgoing, data, SendFlags::RELIABLE).unwrap();
+		}
+
+		let first = ConnectionTransport::receive_messages(&host, incoming, 2);
+		let rest = ConnectionTransport::receive_messages(&host, incoming, 10);
+		let data: Vec<_> = first.iter().chain(&rest).map(|m| &m.data[..]).collect();
+		assert_eq!(data, [&b"one"[..], b"two", b"three"]);
+
+		let numbers: Vec<_> = first
+			.iter()
+			.chain(&rest)
+			.map(|m| m.message_number)
+			.collect();
+		assert_eq!(numbers, [1, 2, 3]);
+		assert_eq!(first[0].peer, NetworkingIdentity::SteamId(SteamId(2)));
+		assert!(ConnectionTransport::receive_messages(&guest, outgoing, 10).is_empty());
+	}
+
+	#[test]
+	fn close_with_linger_keeps_sent_messages() {
+		let network = LoopbackNetwork::new();
+		let host = network.peer(SteamId(1));
+		let guest = network.peer(SteamId(2));
+		let (incoming, outgoing) = connected(&host, &guest);
+
+		ConnectionTransport::send_message(&guest, outgoing, b"bye", SendFlags::RELIABLE).unwrap();
+		assert_eq!(host.close_connection(outgoing, 0, None, true), Err(()));
+		guest.close_connection(outgoing, 0, None, true).unwrap();
+
+		assert_eq!(guest.connection_state(outgoing), ConnectionState::None);
+		assert_eq!(
+			host.connection_state(incoming),
+			ConnectionState::ClosedByPeer
+		);
+		let messages = ConnectionTransport::receive_messages(&host, incoming, 10);
+		assert_eq!(messages.len(), 1);
+		assert_eq!(messages[0].data, b"bye");
+		assert_eq!(
+			ConnectionTransport::send_message(&host, incoming, b"?", SendFlags::RELIABLE),
+			Err(Error::NoConnection)
+		);
+	}
+
+	#[test]
+	fn close_without_linger_drops_messages_in_flight() {
+		let network = LoopbackNetwork::new();
+		let host = network.peer(SteamId(1));
+		let guest = network.peer(SteamId(2));
+		let (incoming, outgoing) = connected(&host, &guest);
+
+		ConnectionTransport::send_message(&guest, outgoing, b"one", SendFlags::RELIABLE).unwrap();
+		assert_eq!(
+			ConnectionTransport::receive_messages(&host, incoming, 10).len(),
+			1
+		);
+		ConnectionTransport::send_message(&guest, outgoing, b"two", SendFlags::RELIABLE).unwrap();
+		guest.close_connection(outgoing, 0, None, false).unwrap();
+
+		assert_eq!(
+			host.connection_state(incoming),
+			ConnectionState::ClosedByPeer
+		);
+		assert!(ConnectionTransport::receive_messages(&host, incoming, 10).is_empty());
+	}
+
+	#[test]
+	fn closing_listen_socket_drops_pending_requests() {
+		let network = LoopbackNetwork::new();
+		let host = network.peer(SteamId(1));
+		let guest = network.peer(SteamId(2));
+
+		let socket = host.listen_p2p(0).unwrap();
+		let outgoing = guest.connect_p2p(&SteamId(1).into(), 0).unwrap();
+		assert_eq!(guest.close_listen_socket(socket), Err(()));
+		host.close_listen_socket(socket).unwrap();
+
+		assert_eq!(
+			guest.connection_state(outgoing),
+			ConnectionState::ClosedByPeer
+		);
+		assert_eq!(next(host.connection_requests(socket)), None);
+	}
+
+	#[test]
+	fn sessions_hold_messages_until_accepted() {
+		let network = LoopbackNetwork::new();
+		let host = network.peer(SteamId(1));
+		let guest = network.peer(SteamId(2));
+
+		MessageTransport::send_message(&guest, &SteamId(1).into(), 3, b"a", SendFlags::RELIABLE)
+			.unwrap();
+		MessageTransport::send_message(&guest, &SteamId(1).into(), 3, b"b", SendFlags::RELIABLE)
+			.unwrap();
+		assert!(MessageTransport::receive_messages(&host, 3, 10).is_empty());
+
+		let request = next(host.session_requests()).unwrap();
+		assert_eq!(request.remote, NetworkingIdentity::SteamId(SteamId(2)));
+		host.accept_session(&request.remote).unwrap();
+
+		let data: Vec<_> = MessageTransport::receive_messages(&host, 3, 10)
+			.into_iter()
+			.map(|m| m.data)
+			.collect();
+		assert_eq!(data, [b"a".to_vec(), b"b".to_vec()]);
+		assert!(MessageTransport::receive_messages(&host, 0, 10).is_empty());
+
+		// Replying needs no acceptance, as the guest started the session.
+		MessageTransport::send_message(&host, &SteamId(2).into(), 0, b"c", SendFlags::RELIABLE)
+			.unwrap();
+		assert_eq!(MessageTransport::receive_messages(&guest, 0, 10).len(), 1);
+	}
+
+	#[test]
+	fn closed_sessions_need_accepting_again() {
+		let network = LoopbackNetwork::new();
+		let host = network.peer(SteamId(1));
+		let guest = network.peer(SteamId(2));
+		let remote = NetworkingIdentity::from(SteamId(2));
+
+		assert_eq!(host.close_session(&remote), Err(()));
+		MessageTransport::send_message(&guest, &SteamId(1).into(), 0, b"a", SendFlags::RELIABLE)
+			.unwrap();
+		host.accept_session(&remote).unwrap();
+		assert_eq!(MessageTransport::receive_messages(&host, 0, 10).len(), 1);
+		host.close_session(&remote).unwrap();
+
+		MessageTransport::send_message(&guest, &SteamId(1).into(), 0, b"b", SendFlags::RELIABLE)
+			.unwrap();
+		assert!(MessageTransport::receive_messages(&host, 0, 10).is_empty());
+		assert_eq!(next(host.session_requests()).unwrap().remote, remote);
+	}
+
+	#[test]
+	fn unknown_peers_are_unreachable() {
+		let network = LoopbackNetwork::new();
+		let guest = network.peer(SteamId(2));
+
+		assert_eq!(
+			MessageTransport::send_message(
+				&guest,
+				&SteamId(9).into(),
+				0,
+				b"a",
+				SendFlags::RELIABLE
+			),
+			Err(Error::NoConnection)
+		);
+	}
+
+	#[test]
+	fn connect_by_ip() {
+		let network = LoopbackNetwork::new();
+		let host = network.peer(SteamId(1));
+		let guest = network.peer(SteamId(2));
+		let addr: SocketAddr = "10.0.0.1:27015".parse().unwrap();
+
+		let socket = host.listen_ip(addr).unwrap();
+		assert_eq!(guest.listen_ip(addr), None);
+
+		let outgoing = guest.connect_ip(addr).unwrap();
+		let incoming = next(host.connection_requests(socket)).unwrap();
+		host.accept_connection(incoming).unwrap();
+
+		let info = guest.connection_info(outgoing).unwrap();
+		assert_eq!(info.remote, NetworkingIdentity::Ip(addr));
+		assert_eq!(info.remote_addr, Some(addr));
+		assert_eq!(info.state, ConnectionState::Connected);
+		let info = host.connection_info(incoming).unwrap();
+		assert_eq!(info.remote, NetworkingIdentity::SteamId(SteamId(2)));
+		assert_eq!(info.listen_socket, Some(socket));
+		assert_eq!(guest.connection_info(incoming), None);
+
+		let unreachable = guest.connect_ip("10.0.0.2:27015".parse().unwrap()).unwrap();
+		assert_eq!(
+			guest.connection_state(unreachable),
+			ConnectionState::ProblemDetectedLocally
+		);
+	}
+
+	#[test]
+	fn status_changes_are_reported() {
+		let network = LoopbackNetwork::new();
+		let host = network.peer(SteamId(1));
+		let guest = network.peer(SteamId(2));
+		let mut host_changes = host.connection_status_changes();
+		let guest_changes = guest.connection_status_changes();
+
+		let (incoming, outgoing) = connected(&host, &guest);
+		executor::spawn(guest.connected(outgoing))
+			.wait_future()
+			.unwrap();
+
+		let states = |changes: Vec<ConnectionStatusChanged>| -> Vec<_> {
+			changes
+				.into_iter()
+				.map(|c| (c.connection, c.old_state, c.info.state))
+				.collect()
+		};
+		assert_eq!(
+			states(take(&mut host_changes, 2)),
+			[
+				(incoming, ConnectionState::None, ConnectionState::Connecting),
+				(
+					incoming,
+					ConnectionState::Connecting,
+					ConnectionState::Connected
+				),
+			]
+		);
+		assert_eq!(
+			states(take(guest_changes, 2)),
+			[
+				(outgoing, ConnectionState::None, ConnectionState::Connecting),
+				(
+					outgoing,
+					ConnectionState::Connecting,
+					ConnectionState::Connected
+				),
+			]
+		);
+
+		let debug = CStr::from_bytes_with_nul(b"bye\0").unwrap();
+		guest
+			.close_connection(outgoing, 1000, Some(debug), false)
+			.unwrap();
+		let closed = next(host_changes).unwrap();
+		assert_eq!(closed.info.state, ConnectionState::ClosedByPeer);
+		assert_eq!(closed.info.end_reason, 1000);
+		assert_eq!(closed.info.end_debug, "bye");
+		assert_eq!(
+			executor::spawn(host.connected(incoming)).wait_future(),
+			Err(Error::NoConnection)
+		);
+	}
+
+	#[test]
+	fn flushed_messages_survive_an_unlingered_close() {
+		let network = LoopbackNetwork::new();
+		let host = network.peer(SteamId(1));
+		let guest = network.peer(SteamId(2));
+		let (incoming, outgoing) = connected(&host, &guest);
+
+		ConnectionTransport::send_message(&guest, outgoing, b"a", SendFlags::RELIABLE).unwrap();
+		ConnectionTransport::send_message(&guest, outgoing, b"b", SendFlags::UNRELIABLE).unwrap();
+		let status = guest.connection_status(outgoing).unwrap();
+		assert_eq!(status.state, ConnectionState::Connected);
+		assert_eq!((status.pending_reliable, status.pending_unreliable), (1, 1));
+
+		guest.flush_messages(outgoing).unwrap();
+		let status = guest.connection_status(outgoing).unwrap();
+		assert_eq!((status.pending_reliable, status.pending_unreliable), (0, 0));
+
+		ConnectionTransport::send_message(&guest, outgoing, b"c", SendFlags::RELIABLE).unwrap();
+		guest.close_connection(outgoing, 0, None, false).unwrap();
+		let messages = ConnectionTransport::receive_messages(&host, incoming, 10);
+		assert_eq!(data(&messages), [&b"a"[..], b"b"]);
+		assert_eq!(host.flush_messages(outgoing), Err(Error::InvalidParam));
+	}
+
+	#[test]
+	fn closing_listen_socket_closes_accepted_connections() {
+		let network = LoopbackNetwork::new();
+		let host = network.peer(SteamId(1));
+		let guest = network.peer(SteamId(2));
+		let (incoming, outgoing) = connected(&host, &guest);
+		let socket = host
+			.connection_info(incoming)
+			.unwrap()
+			.listen_socket
+			.unwrap();
+
+		host.close_listen_socket(socket).unwrap();
+		assert_eq!(host.connection_state(incoming), ConnectionState::None);
+		assert_eq!(
+			guest.connection_state(outgoing),
+			ConnectionState::ClosedByPeer
+		);
+	}
+
+	#[test]
+	fn poll_groups_receive_in_send_order() {
+		let network = LoopbackNetwork::new();
+		let host = network.peer(SteamId(1));
+		let a = network.peer(SteamId(2));
+		let b = network.peer(SteamId(3));
+
+		let socket = host.listen_p2p(0).unwrap();
+		let to_host_a = a.connect_p2p(&host.steam_id().into(), 0).unwrap();
+		let to_host_b = b.connect_p2p(&host.steam_id().into(), 0).unwrap();
+		let requests = take(host.connection_requests(socket), 2);
+		for &connection in &requests {
+			host.accept_connection(connection).unwrap();
+		}
+		let (from_a, from_b) = (requests[0], requests[1]);
+
+		let group = host.create_poll_group().unwrap();
+		host.set_connection_poll_group(from_a, Some(group)).unwrap();
+		host.set_connection_poll_group(from_b, Some(group)).unwrap();
+		host.set_connection_user_data(from_b, 7).unwrap();
+		assert_eq!(a.set_connection_poll_group(to_host_a, Some(group)), Err(()));
+
+		ConnectionTransport::send_message(&a, to_host_a, b"1", SendFlags::RELIABLE).unwrap();
+		ConnectionTransport::send_message(&b, to_host_b, b"2", SendFlags::RELIABLE).unwrap();
+		ConnectionTransport::send_message(&a, to_host_a, b"3", SendFlags::RELIABLE).unwrap();
+
+		let messages = host.receive_messages_on_poll_group(group, 10);
+		assert_eq!(data(&messages), [&b"1"[..], b"2", b"3"]);
+		let connections: Vec<_> = messages.iter().map(|m| m.connection).collect();
+		assert_eq!(connections, [from_a, from_b, from_a]);
+		assert_eq!(messages[1].connection_user_data, 7);
+
+		host.destroy_poll_group(group).unwrap();
+		assert!(host.receive_messages_on_poll_group(group, 10).is_empty());
+		assert_eq!(host.set_connection_poll_group(from_a, Some(group)), Err(()));
+	}
+
+	#[test]
+	fn closing_the_last_channel_closes_the_session() {
+		let network = LoopbackNetwork::new();
+		let host = network.peer(SteamId(1));
+		let guest = network.peer(SteamId(2));
+		let host_id = NetworkingIdentity::from(SteamId(1));
+		let guest_id = NetworkingIdentity::from(SteamId(2));
+		let failures = guest.session_failures();
+
+		MessageTransport::send_message(&guest, &host_id, 1, b"a", SendFlags::RELIABLE).unwrap();
+		MessageTransport::send_message(&guest, &host_id, 2, b"b", SendFlags::RELIABLE).unwrap();
+		assert_eq!(guest.session_info(&host_id).0, ConnectionState::Connecting);
+		assert_eq!(host.session_info(&guest_id).0, ConnectionState::Connecting);
+
+		host.accept_session(&guest_id).unwrap();
+		let (state, info) = guest.session_info(&host_id);
+		assert_eq!(state, ConnectionState::Connected);
+		assert_eq!(info.unwrap().remote, host_id);
+
+		MessageTransport::send_message(&guest, &host_id, 1, b"c", SendFlags::RELIABLE).unwrap();
+		host.close_channel(&guest_id, 1).unwrap();
+		assert!(MessageTransport::receive_messages(&host, 1, 10).is_empty());
+		assert_eq!(host.close_channel(&guest_id, 1), Err(()));
+		assert_eq!(host.session_info(&guest_id).0, ConnectionState::Connected);
+
+		host.close_channel(&guest_id, 2).unwrap();
+		assert_eq!(host.session_info(&guest_id), (ConnectionState::None, None));
+		assert_eq!(guest.session_info(&host_id), (ConnectionState::None, None));
+		let failure = next(failures).unwrap();
+		assert_eq!(failure.info.remote, host_id);
+		assert_eq!(failure.info.state, ConnectionState::ClosedByPeer);
+	}
+
+	#[test]
+	fn channel_messages_stream_received_messages() {
+		let network = LoopbackNetwork::new();
+		let host = network.peer(SteamId(1));
+		let guest = network.peer(SteamId(2));
+
+		MessageTransport::send_message(&guest, &SteamId(1).into(), 4, b"a", SendFlags::RELIABLE)
+			.unwrap();
+		MessageTransport::send_message(&guest, &SteamId(1).into(), 4, b"b", SendFlags::RELIABLE)
+			.unwrap();
+		host.accept_session(&SteamId(2).into()).unwrap();
+
+		let messages = take(host.channel_messages(4), 2);
+		assert_eq!(data(&messages), [&b"a"[..], b"b"]);
+	}
+}
@@ -50,3 +50,118 @@ macro_rules! declare_future {
 		}
 	}
 }
+
+macro_rules! declare_notification {
+	(
+		Data ($id:tt) {
+			$($ident:ident: $ty:ty),*$(,)*
+		} -> $outty:ty;
+
+		map($map:expr);
+	) => {
+		const _: () = {
+			#[repr(packed)]
+			struct Data {
+				$($ident: $ty),*
+			}
+
+			impl crate::Notification for $outty {
+				const ID: u32 = $id;
+
+				unsafe fn from_raw(raw: &[u8]) -> Option<Self> {
+					if raw.len() < std::mem::size_of::<Data>() {
+						return None;
+					}
+
+					let data: Data = std::ptr::read_unaligned(raw.as_ptr() as *const Data);
+					Some(($map)(data))
+				}
+			}
+		};
+	}
+}
+
+/// Declares a struct of stats and achievements that can be loaded from and stored to `UserStats` as a whole.
+///
+/// ```ignore
+/// declare_stats! {
+///     pub struct PlayerStats {
+///         stats {
+///             kills: i32 = "KILLS",
+///             distance: f32 = "DISTANCE_TRAVELED",
+///         }
+///         achievements {
+///             first_blood = "ACH_FIRST_BLOOD",
+///         }
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! declare_stats {
+	(
+		$(#[$meta:meta])*
+		$vis:vis struct $name:ident {
+			stats {
+				$($stat:ident: $stat_ty:ty = $stat_name:literal),*$(,)*
+			}
+			achievements {
+				$($achievement:ident = $achievement_name:literal),*$(,)*
+			}
+		}
+	) => {
+		$(#[$meta])*
+		#[derive(Clone, Debug, Default, PartialEq)]
+		$vis struct $name {
+			$(pub $stat: $stat_ty,)*
+			$(pub $achievement: bool,)*
+		}
+
+		impl $crate::StatsSchema for $name {
+			const ACHIEVEMENTS: &'static [&'static str] = &[$($achievement_name),*];
+			const STATS: &'static [&'static str] = &[$($stat_name),*];
+
+			fn load(stats: &$crate::UserStats<'_>) -> Result<Self, $crate::SchemaMismatch> {
+				let mut mismatch = $crate::SchemaMismatch::default();
+				let loaded = $name {
+					$(
+						$stat: mismatch.check_stat(
+							$stat_name,
+							stats.stat($crate::schema_name(concat!($stat_name, "\0"))),
+						),
+					)*
+					$(
+						$achievement: mismatch.check_achievement(
+							$achievement_name,
+							stats.achievement($crate::schema_name(concat!($achievement_name, "\0"))),
+						),
+					)*
+				};
+				mismatch.into_result(loaded)
+			}
+
+			fn store(&self, stats: &$crate::UserStats<'_>) -> Result<(), $crate::SchemaMismatch> {
+				let mut mismatch = $crate::SchemaMismatch::default();
+				$(
+					mismatch.check_stat(
+						$stat_name,
+						stats
+							.set_stat($crate::schema_name(concat!($stat_name, "\0")), self.$stat)
+							.ok(),
+					);
+				)*
+				$(
+					if self.$achievement {
+						mismatch.check_achievement(
+							$achievement_name,
+							stats
+								.set_achievement($crate::schema_name(concat!($achievement_name, "\0")))
+								.ok()
+								.map(|()| true),
+						);
+					}
+				)*
+				mismatch.into_result(())
+			}
+		}
+	};
+}
@@ -0,0 +1,659 @@
+use const_cstr::const_cstr;
+use derive_more::{Display, LowerHex, UpperHex};
+use std::{ffi::CStr, os::raw::c_char};
+
+use futures::{Async, Future, Poll, Stream};
+
+use crate::{
+	string_from_ptr,
+	APICall,
+	Client,
+	Error,
+	MaybeAPICall,
+	MaybeRaw,
+	Notifications,
+	Pipe,
+	Raw,
+	RawResult,
+	SteamId,
+	User,
+};
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Hash, UpperHex, LowerHex)]
+pub struct Lobby(pub u64);
+
+#[repr(u32)]
+pub enum LobbyType {
+	Private,
+	FriendsOnly,
+	Public,
+	/// Returned by no searches, but can be joined by friends.
+	Invisible,
+}
+
+#[repr(i32)]
+pub enum LobbyComparison {
+	EqualToOrLessThan = -2,
+	LessThan,
+	Equal,
+	GreaterThan,
+	EqualToOrGreaterThan,
+	NotEqual,
+}
+
+#[repr(u32)]
+pub enum LobbyDistanceFilter {
+	Close,
+	Default,
+	Far,
+	Worldwide,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ChatRoomEnterResponse {
+	Success,
+	DoesntExist,
+	NotAllowed,
+	Full,
+	Error,
+	Banned,
+	Limited,
+	ClanDisabled,
+	CommunityBan,
+	MemberBlockedYou,
+	YouBlockedMember,
+	RatelimitExceeded,
+}
+
+impl From<u32> for ChatRoomEnterResponse {
+	fn from(response: u32) -> Self {
+		match response {
+			1 => ChatRoomEnterResponse::Success,
+			2 => ChatRoomEnterResponse::DoesntExist,
+			3 => ChatRoomEnterResponse::NotAllowed,
+			4 => ChatRoomEnterResponse::Full,
+			6 => ChatRoomEnterResponse::Banned,
+			7 => ChatRoomEnterResponse::Limited,
+			8 => ChatRoomEnterResponse::ClanDisabled,
+			9 => ChatRoomEnterResponse::CommunityBan,
+			10 => ChatRoomEnterResponse::MemberBlockedYou,
+			11 => ChatRoomEnterResponse::YouBlockedMember,
+			15 => ChatRoomEnterResponse::RatelimitExceeded,
+			_ => ChatRoomEnterResponse::Error,
+		}
+	}
+}
+
+/// Posted when a lobby is entered, whether created, joined or invited to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LobbyEnter {
+	pub lobby:    Lobby,
+	pub locked:   bool,
+	pub response: ChatRoomEnterResponse,
+}
+
+declare_notification! {
+	Data (504) {
+		lobby:             Lobby,
+		_chat_permissions: u32,
+		locked:            bool,
+		_pad:              [u8; 3],
+		response:          u32,
+	} -> LobbyEnter;
+
+	map(|Data {lobby, locked, response, ..}| LobbyEnter {
+		lobby,
+		locked,
+		response: response.into(),
+	});
+}
+
+/// Posted when the data of a lobby, or of one of its members, has changed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LobbyDataUpdate {
+	pub lobby:   Lobby,
+	/// The member whose data changed, or the lobby itself.
+	pub member:  SteamId,
+	pub success: bool,
+}
+
+declare_notification! {
+	Data (505) {
+		lobby:   Lobby,
+		member:  SteamId,
+		success: u8,
+	} -> LobbyDataUpdate;
+
+	map(|Data {lobby, member, success}| LobbyDataUpdate {
+		lobby,
+		member,
+		success: success != 0,
+	});
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ChatMemberStateChange {
+	Entered,
+	Left,
+	Disconnected,
+	Kicked,
+	Banned,
+}
+
+impl ChatMemberStateChange {
+	/// Decodes `EChatMemberStateChange` flags, preferring the most specific reason when several
+	/// are set, e.g. `Kicked` over the `Left` that comes with it.
+	fn from_flags(flags: u32) -> Option<Self> {
+		if flags & 0x10 != 0 {
+			Some(ChatMemberStateChange::Banned)
+		} else if flags & 0x8 != 0 {
+			Some(ChatMemberStateChange::Kicked)
+		} else if flags & 0x4 != 0 {
+			Some(ChatMemberStateChange::Disconnected)
+		} else if flags & 0x2 != 0 {
+			Some(ChatMemberStateChange::Left)
+		} else if flags & 0x1 != 0 {
+			Some(ChatMemberStateChange::Entered)
+		} else {
+			None
+		}
+	}
+}
+
+/// Posted when a user joins or leaves a lobby.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LobbyChatUpdate {
+	pub lobby:         Lobby,
+	pub user:          SteamId,
+	/// The user that caused the change, e.g. by kicking `user`.
+	pub making_change: SteamId,
+	/// `None` if Steam reported no change this crate knows of.
+	pub change:        Option<ChatMemberStateChange>,
+}
+
+declare_notification! {
+	Data (506) {
+		lobby:         Lobby,
+		user:          SteamId,
+		making_change: SteamId,
+		change:        u32,
+	} -> LobbyChatUpdate;
+
+	map(|Data {lobby, user, making_change, change}| LobbyChatUpdate {
+		lobby,
+		user,
+		making_change,
+		change: ChatMemberStateChange::from_flags(change),
+	});
+}
+
+struct RawLobbyChatMsg {
+	lobby:   Lobby,
+	chat_id: i32,
+}
+
+declare_notification! {
+	Data (507) {
+		lobby:       Lobby,
+		_user:       SteamId,
+		_entry_type: u8,
+		_pad:        [u8; 3],
+		chat_id:     u32,
+	} -> RawLobbyChatMsg;
+
+	map(|Data {lobby, chat_id, ..}| RawLobbyChatMsg {
+		lobby,
+		chat_id: chat_id as i32,
+	});
+}
+
+/// A chat message sent to a lobby.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LobbyChatMsg {
+	pub lobby: Lobby,
+	pub user:  SteamId,
+	pub data:  Vec<u8>,
+}
+
+/// The maximum size of a lobby chat message.
+pub const LOBBY_CHAT_MSG_MAX: usize = 4 * 1024;
+
+interface!(Matchmaking);
+impl<'a> Matchmaking<'a> {
+	pub fn new(client: &Client<'a>) -> Option<Self> {
+		let raw = unsafe {
+			SteamAPI_ISteamClient_GetISteamMatchmaking(
+				client.raw.clone(),
+				client.user(),
+				client.pipe(),
+				const_cstr!("SteamMatchMaking009").as_ptr(),
+			)
+			.check()?
+		};
+		let utils = client.utils.clone();
+		let callbacks = client.callbacks.clone();
+
+		Some(Matchmaking {
+			raw,
+			utils,
+			callbacks,
+		})
+	}
+
+	/// Creates a lobby and enters it.
+	pub fn create_lobby(
+		&'a self,
+		lobby_type: LobbyType,
+		max_members: u32,
+	) -> Option<impl Future<Item = Lobby, Error = Error> + 'a> {
+		declare_future! {
+			Data (513) {
+				result: RawResult,
+				lobby:  Lobby,
+			} -> Lobby;
+
+			map(|Data {result, lobby}| Result::from(result).map(|_| lobby));
+		}
+
+		let api_call = unsafe {
+			SteamAPI_ISteamMatchmaking_CreateLobby(self.raw.clone(), lobby_type, max_members as i32)
+		};
+
+		Some(Handle {
+			api_call: unsafe { APICall::new(api_call)? },
+			utils:    self.utils.clone(),
+		})
+	}
+
+	pub fn join_lobby(
+		&'a self,
+		lobby: Lobby,
+	) -> Option<impl Future<Item = LobbyEnter, Error = Error> + 'a> {
+		declare_future! {
+			Data (504) {
+				lobby:             Lobby,
+				_chat_permissions: u32,
+				locked:            bool,
+				_pad:              [u8; 3],
+				response:          u32,
+			} -> LobbyEnter;
+
+			map(|Data {lobby, locked, response, ..}| Ok(LobbyEnter {
+				lobby,
+				locked,
+				response: response.into(),
+			}));
+		}
+
+		let api_call = unsafe { SteamAPI_ISteamMatchmaking_JoinLobby(self.raw.clone(), lobby) };
+
+		Some(Handle {
+			api_call: unsafe { APICall::new(api_call)? },
+			utils:    self.utils.clone(),
+		})
+	}
+
+	pub fn leave_lobby(&self, lobby: Lobby) {
+		unsafe { SteamAPI_ISteamMatchmaking_LeaveLobby(self.raw.clone(), lobby) }
+	}
+
+	/// Builds a lobby search; the filters apply to the next `LobbyListRequest::request` only.
+	pub fn lobby_list(&'a self) -> LobbyListRequest<'a> {
+		LobbyListRequest { matchmaking: self }
+	}
+
+	pub fn lobby_data(&self, lobby: Lobby, key: &CStr) -> Option<String> {
+		string_from_ptr(unsafe {
+			SteamAPI_ISteamMatchmaking_GetLobbyData(self.raw.clone(), lobby, key.as_ptr())
+		})
+		.filter(|value| !value.is_empty())
+	}
+
+	/// All the data of a lobby, as key-value pairs.
+	pub fn lobby_data_entries(&self, lobby: Lobby) -> Vec<(String, String)> {
+		let count =
+			unsafe { SteamAPI_ISteamMatchmaking_GetLobbyDataCount(self.raw.clone(), lobby) };
+		(0..count)
+			.filter_map(|i| {
+				let mut key = [0 as c_char; 256];
+				let mut value = [0 as c_char; 8192];
+				if unsafe {
+					SteamAPI_ISteamMatchmaking_GetLobbyDataByIndex(
+						self.raw.clone(),
+						lobby,
+						i,
+						key.as_mut_ptr(),
+						key.len() as i32,
+						value.as_mut_ptr(),
+						value.len() as i32,
+					)
+				} {
+					Some((
+						string_from_ptr(key.as_ptr())?,
+						string_from_ptr(value.as_ptr())?,
+					))
+				} else {
+					None
+				}
+			})
+			.collect()
+	}
+
+	/// Sets lobby data, which only the owner can do.
+	pub fn set_lobby_data(&self, lobby: Lobby, key: &CStr, value: &CStr) -> Result<(), ()> {
+		if unsafe {
+			SteamAPI_ISteamMatchmaking_SetLobbyData(
+				self.raw.clone(),
+				lobby,
+				key.as_ptr(),
+				value.as_ptr(),
+			)
+		} {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	pub fn delete_lobby_data(&self, lobby: Lobby, key: &CStr) -> Result<(), ()> {
+		if unsafe {
+			SteamAPI_ISteamMatchmaking_DeleteLobbyData(self.raw.clone(), lobby, key.as_ptr())
+		} {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	pub fn lobby_member_data(&self, lobby: Lobby, member: SteamId, key: &CStr) -> Option<String> {
+		string_from_ptr(unsafe {
+			SteamAPI_ISteamMatchmaking_GetLobbyMemberData(
+				self.raw.clone(),
+				lobby,
+				member,
+				key.as_ptr(),
+			)
+		})
+		.filter(|value| !value.is_empty())
+	}
+
+	/// Sets the current user's data in the lobby.
+	pub fn set_lobby_member_data(&self, lobby: Lobby, key: &CStr, value: &CStr) {
+		unsafe {
+			SteamAPI_ISteamMatchmaking_SetLobbyMemberData(
+				self.raw.clone(),
+				lobby,
+				key.as_ptr(),
+				value.as_ptr(),
+			)
+		}
+	}
+
+	pub fn lobby_owner(&self, lobby: Lobby) -> Option<SteamId> {
+		match unsafe { SteamAPI_ISteamMatchmaking_GetLobbyOwner(self.raw.clone(), lobby) } {
+			SteamId(0) => None,
+			owner => Some(owner),
+		}
+	}
+
+	/// Transfers ownership of the lobby, which only the owner can do.
+	pub fn set_lobby_owner(&self, lobby: Lobby, owner: SteamId) -> Result<(), ()> {
+		if unsafe { SteamAPI_ISteamMatchmaking_SetLobbyOwner(self.raw.clone(), lobby, owner) } {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	pub fn lobby_members(&self, lobby: Lobby) -> impl Iterator<Item = SteamId> + '_ {
+		let count =
+			unsafe { SteamAPI_ISteamMatchmaking_GetNumLobbyMembers(self.raw.clone(), lobby) };
+		(0..count).map(move |i| unsafe {
+			SteamAPI_ISteamMatchmaking_GetLobbyMemberByIndex(self.raw.clone(), lobby, i)
+		})
+	}
+
+	pub fn lobby_member_limit(&self, lobby: Lobby) -> u32 {
+		let limit =
+			unsafe { SteamAPI_ISteamMatchmaking_GetLobbyMemberLimit(self.raw.clone(), lobby) };
+		limit.max(0) as u32
+	}
+
+	pub fn set_lobby_member_limit(&self, lobby: Lobby, limit: u32) -> Result<(), ()> {
+		if unsafe {
+			SteamAPI_ISteamMatchmaking_SetLobbyMemberLimit(self.raw.clone(), lobby, limit as i32)
+		} {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	pub fn set_lobby_type(&self, lobby: Lobby, lobby_type: LobbyType) -> Result<(), ()> {
+		if unsafe { SteamAPI_ISteamMatchmaking_SetLobbyType(self.raw.clone(), lobby, lobby_type) } {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	pub fn set_lobby_joinable(&self, lobby: Lobby, joinable: bool) -> Result<(), ()> {
+		if unsafe { SteamAPI_ISteamMatchmaking_SetLobbyJoinable(self.raw.clone(), lobby, joinable) }
+		{
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	/// Sends a message of up to `LOBBY_CHAT_MSG_MAX` bytes to every member of the lobby.
+	pub fn send_lobby_chat_msg(&self, lobby: Lobby, data: impl AsRef<[u8]>) -> Result<(), ()> {
+		let data = data.as_ref();
+		if data.len() > LOBBY_CHAT_MSG_MAX {
+			return Err(());
+		}
+
+		if unsafe {
+			SteamAPI_ISteamMatchmaking_SendLobbyChatMsg(
+				self.raw.clone(),
+				lobby,
+				data.as_ptr(),
+				data.len() as i32,
+			)
+		} {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	pub fn lobby_enters(&self) -> Notifications<'a, LobbyEnter> {
+		self.callbacks.subscribe()
+	}
+
+	pub fn lobby_data_updates(&self) -> Notifications<'a, LobbyDataUpdate> {
+		self.callbacks.subscribe()
+	}
+
+	pub fn lobby_chat_updates(&self) -> Notifications<'a, LobbyChatUpdate> {
+		self.callbacks.subscribe()
+	}
+
+	pub fn lobby_chat_msgs(&'a self) -> impl Stream<Item = LobbyChatMsg, Error = Error> + 'a {
+		self.callbacks.subscribe::<RawLobbyChatMsg>().and_then(
+			move |RawLobbyChatMsg { lobby, chat_id }| {
+				let mut user = SteamId(0);
+				let mut data = vec![0; LOBBY_CHAT_MSG_MAX];
+				let mut entry_type = 0u32;
+				let len = unsafe {
+					SteamAPI_ISteamMatchmaking_GetLobbyChatEntry(
+						self.raw.clone(),
+						lobby,
+						chat_id,
+						&mut user as *mut SteamId,
+						data.as_mut_ptr(),
+						data.len() as i32,
+						&mut entry_type as *mut u32,
+					)
+				};
+				data.truncate(len.max(0) as usize);
+				Ok(LobbyChatMsg { lobby, user, data })
+			},
+		)
+	}
+}
+
+/// A lobby search, narrowed down by filters which are combined with AND.
+pub struct LobbyListRequest<'a> {
+	matchmaking: &'a Matchmaking<'a>,
+}
+
+impl<'a> LobbyListRequest<'a> {
+	pub fn string_filter(self, key: &CStr, value: &CStr, comparison: LobbyComparison) -> Self {
+		unsafe {
+			SteamAPI_ISteamMatchmaking_AddRequestLobbyListStringFilter(
+				self.matchmaking.raw.clone(),
+				key.as_ptr(),
+				value.as_ptr(),
+				comparison,
+			)
+		};
+		self
+	}
+
+	pub fn numerical_filter(self, key: &CStr, value: i32, comparison: LobbyComparison) -> Self {
+		unsafe {
+			SteamAPI_ISteamMatchmaking_AddRequestLobbyListNumericalFilter(
+				self.matchmaking.raw.clone(),
+				key.as_ptr(),
+				value,
+				comparison,
+			)
+		};
+		self
+	}
+
+	/// Sorts the results by how close `key` is to `value`.
+	pub fn near_value_filter(self, key: &CStr, value: i32) -> Self {
+		unsafe {
+			SteamAPI_ISteamMatchmaking_AddRequestLobbyListNearValueFilter(
+				self.matchmaking.raw.clone(),
+				key.as_ptr(),
+				value,
+			)
+		};
+		self
+	}
+
+	pub fn slots_available(self, slots: u32) -> Self {
+		unsafe {
+			SteamAPI_ISteamMatchmaking_AddRequestLobbyListFilterSlotsAvailable(
+				self.matchmaking.raw.clone(),
+				slots as i32,
+			)
+		};
+		self
+	}
+
+	pub fn distance(self, distance: LobbyDistanceFilter) -> Self {
+		unsafe {
+			SteamAPI_ISteamMatchmaking_AddRequestLobbyListDistanceFilter(
+				self.matchmaking.raw.clone(),
+				distance,
+			)
+		};
+		self
+	}
+
+	pub fn result_count(self, count: u32) -> Self {
+		unsafe {
+			SteamAPI_ISteamMatchmaking_AddRequestLobbyListResultCountFilter(
+				self.matchmaking.raw.clone(),
+				count as i32,
+			)
+		};
+		self
+	}
+
+	pub fn request(self) -> Option<impl Future<Item = Vec<Lobby>, Error = Error> + 'a> {
+		declare_future! {
+			Data (510) {
+				count: u32,
+			} -> u32;
+
+			map(|Data {count}| Ok(count));
+		}
+
+		let matchmaking = self.matchmaking;
+		let api_call =
+			unsafe { SteamAPI_ISteamMatchmaking_RequestLobbyList(matchmaking.raw.clone()) };
+
+		let handle = Handle {
+			api_call: unsafe { APICall::new(api_call)? },
+			utils:    matchmaking.utils.clone(),
+		};
+		Some(handle.map(move |count| {
+			(0..count as i32)
+				.map(|i| unsafe {
+					SteamAPI_ISteamMatchmaking_GetLobbyByIndex(matchmaking.raw.clone(), i)
+				})
+				.collect()
+		}))
+	}
+}
+
+steam_extern! {
+	fn SteamAPI_ISteamClient_GetISteamMatchmaking<'a>(a: Raw<Client<'a>>, b: User<'a>, c: Pipe<'a>, d: *const c_char) -> MaybeRaw<Matchmaking<'a>>;
+
+	fn SteamAPI_ISteamMatchmaking_CreateLobby(a: Raw<Matchmaking<'_>>, b: LobbyType, c: i32) -> MaybeAPICall;
+	fn SteamAPI_ISteamMatchmaking_JoinLobby(a: Raw<Matchmaking<'_>>, b: Lobby)               -> MaybeAPICall;
+	fn SteamAPI_ISteamMatchmaking_LeaveLobby(a: Raw<Matchmaking<'_>>, b: Lobby);
+
+	fn SteamAPI_ISteamMatchmaking_RequestLobbyList(a: Raw<Matchmaking<'_>>)       -> MaybeAPICall;
+	fn SteamAPI_ISteamMatchmaking_GetLobbyByIndex(a: Raw<Matchmaking<'_>>, b: i32) -> Lobby;
+
+	fn SteamAPI_ISteamMatchmaking_AddRequestLobbyListStringFilter(a: Raw<Matchmaking<'_>>, b: *const c_char, c: *const c_char, d: LobbyComparison);
+	fn SteamAPI_ISteamMatchmaking_AddRequestLobbyListNumericalFilter(a: Raw<Matchmaking<'_>>, b: *const c_char, c: i32, d: LobbyComparison);
+	fn SteamAPI_ISteamMatchmaking_AddRequestLobbyListNearValueFilter(a: Raw<Matchmaking<'_>>, b: *const c_char, c: i32);
+	fn SteamAPI_ISteamMatchmaking_AddRequestLobbyListFilterSlotsAvailable(a: Raw<Matchmaking<'_>>, b: i32);
+	fn SteamAPI_ISteamMatchmaking_AddRequestLobbyListDistanceFilter(a: Raw<Matchmaking<'_>>, b: LobbyDistanceFilter);
+	fn SteamAPI_ISteamMatchmaking_AddRequestLobbyListResultCountFilter(a: Raw<Matchmaking<'_>>, b: i32);
+
+	fn SteamAPI_ISteamMatchmaking_GetLobbyData(a: Raw<Matchmaking<'_>>, b: Lobby, c: *const c_char)                    -> *const c_char;
+	fn SteamAPI_ISteamMatchmaking_SetLobbyData(a: Raw<Matchmaking<'_>>, b: Lobby, c: *const c_char, d: *const c_char) -> bool;
+	fn SteamAPI_ISteamMatchmaking_DeleteLobbyData(a: Raw<Matchmaking<'_>>, b: Lobby, c: *const c_char)                 -> bool;
+	fn SteamAPI_ISteamMatchmaking_GetLobbyDataCount(a: Raw<Matchmaking<'_>>, b: Lobby)                                 -> i32;
+	fn SteamAPI_ISteamMatchmaking_GetLobbyDataByIndex(
+		a: Raw<Matchmaking<'_>>,
+		b: Lobby,
+		c: i32,
+		d: *mut c_char,
+		e: i32,
+		f: *mut c_char,
+		g: i32
+	) -> bool;
+
+	fn SteamAPI_ISteamMatchmaking_GetLobbyMemberData(a: Raw<Matchmaking<'_>>, b: Lobby, c: SteamId, d: *const c_char) -> *const c_char;
+	fn SteamAPI_ISteamMatchmaking_SetLobbyMemberData(a: Raw<Matchmaking<'_>>, b: Lobby, c: *const c_char, d: *const c_char);
+
+	fn SteamAPI_ISteamMatchmaking_GetLobbyOwner(a: Raw<Matchmaking<'_>>, b: Lobby)                 -> SteamId;
+	fn SteamAPI_ISteamMatchmaking_SetLobbyOwner(a: Raw<Matchmaking<'_>>, b: Lobby, c: SteamId)     -> bool;
+	fn SteamAPI_ISteamMatchmaking_GetNumLobbyMembers(a: Raw<Matchmaking<'_>>, b: Lobby)            -> i32;
+	fn SteamAPI_ISteamMatchmaking_GetLobbyMemberByIndex(a: Raw<Matchmaking<'_>>, b: Lobby, c: i32) -> SteamId;
+	fn SteamAPI_ISteamMatchmaking_GetLobbyMemberLimit(a: Raw<Matchmaking<'_>>, b: Lobby)           -> i32;
+	fn SteamAPI_ISteamMatchmaking_SetLobbyMemberLimit(a: Raw<Matchmaking<'_>>, b: Lobby, c: i32)   -> bool;
+	fn SteamAPI_ISteamMatchmaking_SetLobbyType(a: Raw<Matchmaking<'_>>, b: Lobby, c: LobbyType)    -> bool;
+	fn SteamAPI_ISteamMatchmaking_SetLobbyJoinable(a: Raw<Matchmaking<'_>>, b: Lobby, c: bool)     -> bool;
+
+	fn SteamAPI_ISteamMatchmaking_SendLobbyChatMsg(a: Raw<Matchmaking<'_>>, b: Lobby, c: *const u8, d: i32) -> bool;
+	fn SteamAPI_ISteamMatchmaking_GetLobbyChatEntry(
+		a: Raw<Matchmaking<'_>>,
+		b: Lobby,
+		c: i32,
+		d: *mut SteamId,
+		e: *mut u8,
+		f: i32,
+		g: *mut u32
+	) -> i32;
+}
@@ -0,0 +1,616 @@
+use const_cstr::const_cstr;
+use std::{
+	cell::{Cell, RefCell},
+	collections::VecDeque,
+	ffi::CStr,
+	net::{Ipv4Addr, SocketAddrV4},
+	os::raw::{c_char, c_void},
+	time::Duration,
+};
+
+use futures::{task, Async, Future, Poll, Stream};
+
+use crate::{string_from_ptr, Client, Error, MaybeRaw, Pipe, Raw, SteamId, User};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ServerList {
+	Internet,
+	Lan,
+	Friends,
+	Favorites,
+	History,
+	Spectator,
+}
+
+/// A game server as reported by the server browser.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ServerInfo {
+	/// The address players connect to.
+	pub addr:        SocketAddrV4,
+	pub query_port:  u16,
+	pub name:        String,
+	pub map:         String,
+	pub game_dir:    String,
+	pub description: String,
+	pub appid:       u32,
+	pub ping:        Duration,
+	pub players:     u32,
+	pub max_players: u32,
+	pub bot_players: u32,
+	pub password:    bool,
+	pub secure:      bool,
+	pub version:     i32,
+	pub tags:        Vec<String>,
+	pub steam_id:    SteamId,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlayerInfo {
+	pub name:        String,
+	pub score:       i32,
+	pub time_played: Duration,
+}
+
+#[repr(C)]
+struct ServerNetAdr {
+	connection_port: u16,
+	query_port:      u16,
+	ip:              u32,
+}
+
+#[repr(C)]
+struct RawServerInfo {
+	addr: ServerNetAdr,
+	ping: i32,
+	_had_successful_response: bool,
+	_do_not_refresh: bool,
+	game_dir: [c_char; 32],
+	map: [c_char; 32],
+	description: [c_char; 64],
+	appid: u32,
+	players: i32,
+	max_players: i32,
+	bot_players: i32,
+	password: bool,
+	secure: bool,
+	_time_last_played: u32,
+	version: i32,
+	name: [c_char; 64],
+	tags: [c_char; 128],
+	steam_id: SteamId,
+}
+
+impl From<&RawServerInfo> for ServerInfo {
+	fn from(raw: &RawServerInfo) -> Self {
+		let string = |s: &[c_char]| string_from_ptr(s.as_ptr()).unwrap_or_default();
+		ServerInfo {
+			addr:        SocketAddrV4::new(Ipv4Addr::from(raw.addr.ip), raw.addr.connection_port),
+			query_port:  raw.addr.query_port,
+			name:        string(&raw.name),
+			map:         string(&raw.map),
+			game_dir:    string(&raw.game_dir),
+			description: string(&raw.description),
+			appid:       raw.appid,
+			ping:        Duration::from_millis(raw.ping.max(0) as u64),
+			players:     raw.players.max(0) as u32,
+			max_players: raw.max_players.max(0) as u32,
+			bot_players: raw.bot_players.max(0) as u32,
+			password:    raw.password,
+			secure:      raw.secure,
+			version:     raw.version,
+			tags:        string(&raw.tags)
+				.split(',')
+				.filter(|tag| !tag.is_empty())
+				.map(String::from)
+				.collect(),
+			steam_id:    raw.steam_id,
+		}
+	}
+}
+
+#[repr(C)]
+struct KeyValuePair {
+	key:   [c_char; 256],
+	value: [c_char; 256],
+}
+
+impl KeyValuePair {
+	fn new(key: &CStr, value: &CStr) -> Self {
+		let mut pair = KeyValuePair {
+			key:   [0; 256],
+			value: [0; 256],
+		};
+		for (dst, src) in pair.key[..255].iter_mut().zip(key.to_bytes()) {
+			*dst = *src as c_char;
+		}
+		for (dst, src) in pair.value[..255].iter_mut().zip(value.to_bytes()) {
+			*dst = *src as c_char;
+		}
+		pair
+	}
+}
+
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+struct ServerListRequest(*mut u8);
+
+#[repr(transparent)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct ServerQueryHandle(i32);
+
+const INVALID_QUERY: ServerQueryHandle = ServerQueryHandle(-1);
+
+// The response objects below are passed to Steam as C++ objects,
+// so each starts with a pointer to a table of its virtual methods.
+
+#[repr(C)]
+struct ServerListVTable {
+	server_responded: unsafe extern "C" fn(*const ServerListResponse, ServerListRequest, i32),
+	server_failed_to_respond:
+		unsafe extern "C" fn(*const ServerListResponse, ServerListRequest, i32),
+	refresh_complete: unsafe extern "C" fn(*const ServerListResponse, ServerListRequest, u32),
+}
+
+#[repr(C)]
+struct ServerListResponse {
+	vtable:    &'static ServerListVTable,
+	responded: RefCell<VecDeque<i32>>,
+	complete:  Cell<bool>,
+}
+
+static SERVER_LIST_VTABLE: ServerListVTable = {
+	unsafe extern "C" fn server_responded(
+		this: *const ServerListResponse,
+		_: ServerListRequest,
+		i: i32,
+	) {
+		(*this).responded.borrow_mut().push_back(i);
+	}
+
+	unsafe extern "C" fn server_failed_to_respond(
+		_: *const ServerListResponse,
+		_: ServerListRequest,
+		_: i32,
+	) {
+	}
+
+	unsafe extern "C" fn refresh_complete(
+		this: *const ServerListResponse,
+		_: ServerListRequest,
+		_: u32,
+	) {
+		(*this).complete.set(true);
+	}
+
+	ServerListVTable {
+		server_responded,
+		server_failed_to_respond,
+		refresh_complete,
+	}
+};
+
+#[repr(C)]
+struct PingVTable {
+	server_responded:         unsafe extern "C" fn(*const PingResponse, *const RawServerInfo),
+	server_failed_to_respond: unsafe extern "C" fn(*const PingResponse),
+}
+
+#[repr(C)]
+struct PingResponse {
+	vtable: &'static PingVTable,
+	result: RefCell<Option<Result<ServerInfo, Error>>>,
+}
+
+static PING_VTABLE: PingVTable = {
+	unsafe extern "C" fn server_responded(this: *const PingResponse, server: *const RawServerInfo) {
+		*(*this).result.borrow_mut() = Some(Ok((&*server).into()));
+	}
+
+	unsafe extern "C" fn server_failed_to_respond(this: *const PingResponse) {
+		*(*this).result.borrow_mut() = Some(Err(Error::Timeout));
+	}
+
+	PingVTable {
+		server_responded,
+		server_failed_to_respond,
+	}
+};
+
+#[repr(C)]
+struct PlayersVTable {
+	add_player_to_list: unsafe extern "C" fn(*const PlayersResponse, *const c_char, i32, f32),
+	players_failed_to_respond: unsafe extern "C" fn(*const PlayersResponse),
+	players_refresh_complete:  unsafe extern "C" fn(*const PlayersResponse),
+}
+
+#[repr(C)]
+struct PlayersResponse {
+	vtable:  &'static PlayersVTable,
+	players: RefCell<Vec<PlayerInfo>>,
+	result:  Cell<Option<Result<(), Error>>>,
+}
+
+static PLAYERS_VTABLE: PlayersVTable = {
+	unsafe extern "C" fn add_player_to_list(
+		this: *const PlayersResponse,
+		name: *const c_char,
+		score: i32,
+		time_played: f32,
+	) {
+		(*this).players.borrow_mut().push(PlayerInfo {
+			name: string_from_ptr(name).unwrap_or_default(),
+			score,
+			time_played: Duration::from_millis((time_played.max(0.0) * 1000.0) as u64),
+		});
+	}
+
+	unsafe extern "C" fn players_failed_to_respond(this: *const PlayersResponse) {
+		(*this).result.set(Some(Err(Error::Timeout)));
+	}
+
+	unsafe extern "C" fn players_refresh_complete(this: *const PlayersResponse) {
+		(*this).result.set(Some(Ok(())));
+	}
+
+	PlayersVTable {
+		add_player_to_list,
+		players_failed_to_respond,
+		players_refresh_complete,
+	}
+};
+
+#[repr(C)]
+struct RulesVTable {
+	rules_responded: unsafe extern "C" fn(*const RulesResponse, *const c_char, *const c_char),
+	rules_failed_to_respond: unsafe extern "C" fn(*const RulesResponse),
+	rules_refresh_complete:  unsafe extern "C" fn(*const RulesResponse),
+}
+
+#[repr(C)]
+struct RulesResponse {
+	vtable: &'static RulesVTable,
+	rules:  RefCell<Vec<(String, String)>>,
+	result: Cell<Option<Result<(), Error>>>,
+}
+
+static RULES_VTABLE: RulesVTable = {
+	unsafe extern "C" fn rules_responded(
+		this: *const RulesResponse,
+		rule: *const c_char,
+		value: *const c_char,
+	) {
+		let rule = string_from_ptr(rule).unwrap_or_default();
+		let value = string_from_ptr(value).unwrap_or_default();
+		(*this).rules.borrow_mut().push((rule, value));
+	}
+
+	unsafe extern "C" fn rules_failed_to_respond(this: *const RulesResponse) {
+		(*this).result.set(Some(Err(Error::Timeout)));
+	}
+
+	unsafe extern "C" fn rules_refresh_complete(this: *const RulesResponse) {
+		(*this).result.set(Some(Ok(())));
+	}
+
+	RulesVTable {
+		rules_responded,
+		rules_failed_to_respond,
+		rules_refresh_complete,
+	}
+};
+
+trait QueryResponse {
+	type Item;
+
+	fn take(&self) -> Option<Result<Self::Item, Error>>;
+}
+
+impl QueryResponse for PingResponse {
+	type Item = ServerInfo;
+
+	fn take(&self) -> Option<Result<Self::Item, Error>> {
+		self.result.borrow_mut().take()
+	}
+}
+
+impl QueryResponse for PlayersResponse {
+	type Item = Vec<PlayerInfo>;
+
+	fn take(&self) -> Option<Result<Self::Item, Error>> {
+		self.result
+			.take()
+			.map(|result| result.map(|()| self.players.replace(Vec::new())))
+	}
+}
+
+impl QueryResponse for RulesResponse {
+	type Item = Vec<(String, String)>;
+
+	fn take(&self) -> Option<Result<Self::Item, Error>> {
+		self.result
+			.take()
+			.map(|result| result.map(|()| self.rules.replace(Vec::new())))
+	}
+}
+
+struct ServerQuery<'a, R> {
+	servers:  &'a MatchmakingServers<'a>,
+	handle:   ServerQueryHandle,
+	response: Box<R>,
+	done:     bool,
+}
+
+impl<R: QueryResponse> Future for ServerQuery<'_, R> {
+	type Error = Error;
+	type Item = R::Item;
+
+	fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+		self.servers.callbacks.run();
+
+		match self.response.take() {
+			Some(result) => {
+				self.done = true;
+				result.map(Async::Ready)
+			},
+			None => {
+				task::current().notify();
+				Ok(Async::NotReady)
+			},
+		}
+	}
+}
+
+impl<R> Drop for ServerQuery<'_, R> {
+	fn drop(&mut self) {
+		if !self.done {
+			unsafe {
+				SteamAPI_ISteamMatchmakingServers_CancelServerQuery(
+					self.servers.raw.clone(),
+					self.handle,
+				)
+			}
+		}
+	}
+}
+
+/// The servers of a server list, as they respond.
+struct ServerListStream<'a> {
+	servers:  &'a MatchmakingServers<'a>,
+	request:  ServerListRequest,
+	response: Box<ServerListResponse>,
+	_filters: Vec<KeyValuePair>,
+}
+
+impl Stream for ServerListStream<'_> {
+	type Error = Error;
+	type Item = ServerInfo;
+
+	fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+		if self.response.responded.borrow().is_empty() {
+			self.servers.callbacks.run();
+		}
+
+		let responded = self.response.responded.borrow_mut().pop_front();
+		match responded {
+			Some(i) => {
+				let server = unsafe {
+					SteamAPI_ISteamMatchmakingServers_GetServerDetails(
+						self.servers.raw.clone(),
+						self.request,
+						i,
+					)
+				};
+				if server.is_null() {
+					Err(Error::Fail)
+				} else {
+					Ok(Async::Ready(Some(unsafe { &*server }.into())))
+				}
+			},
+			None if self.response.complete.get() => Ok(Async::Ready(None)),
+			None => {
+				task::current().notify();
+				Ok(Async::NotReady)
+			},
+		}
+	}
+}
+
+impl Drop for ServerListStream<'_> {
+	fn drop(&mut self) {
+		unsafe {
+			SteamAPI_ISteamMatchmakingServers_ReleaseRequest(self.servers.raw.clone(), self.request)
+		}
+	}
+}
+
+interface!(MatchmakingServers);
+impl<'a> MatchmakingServers<'a> {
+	pub fn new(client: &Client<'a>) -> Option<Self> {
+		let raw = unsafe {
+			SteamAPI_ISteamClient_GetISteamMatchmakingServers(
+				client.raw.clone(),
+				client.user(),
+				client.pipe(),
+				const_cstr!("SteamMatchMakingServers002").as_ptr(),
+			)
+			.check()?
+		};
+		let utils = client.utils.clone();
+		let callbacks = client.callbacks.clone();
+
+		Some(MatchmakingServers {
+			raw,
+			utils,
+			callbacks,
+		})
+	}
+
+	/// Lists the servers of `appid`, ending once every server has been queried.
+	///
+	/// `filters` are key-value pairs such as `("map", "de_dust")`, and are ignored for LAN lists.
+	pub fn server_list(
+		&'a self,
+		appid: u32,
+		list: ServerList,
+		filters: &[(&CStr, &CStr)],
+	) -> Option<impl Stream<Item = ServerInfo, Error = Error> + 'a> {
+		let response = Box::new(ServerListResponse {
+			vtable:    &SERVER_LIST_VTABLE,
+			responded: RefCell::new(VecDeque::new()),
+			complete:  Cell::new(false),
+		});
+		let filters: Vec<_> = filters
+			.iter()
+			.map(|(key, value)| KeyValuePair::new(key, value))
+			.collect();
+		let mut filter_ptrs: Vec<_> = filters.iter().map(|f| f as *const KeyValuePair).collect();
+
+		let request = unsafe {
+			let raw = self.raw.clone();
+			let f = filter_ptrs.as_mut_ptr();
+			let n = filter_ptrs.len() as u32;
+			let r = &*response as *const ServerListResponse as *const c_void;
+			match list {
+				ServerList::Internet => {
+					SteamAPI_ISteamMatchmakingServers_RequestInternetServerList(raw, appid, f, n, r)
+				},
+				ServerList::Lan => {
+					SteamAPI_ISteamMatchmakingServers_RequestLANServerList(raw, appid, r)
+				},
+				ServerList::Friends => {
+					SteamAPI_ISteamMatchmakingServers_RequestFriendsServerList(raw, appid, f, n, r)
+				},
+				ServerList::Favorites => {
+					SteamAPI_ISteamMatchmakingServers_RequestFavoritesServerList(
+						raw, appid, f, n, r,
+					)
+				},
+				ServerList::History => {
+					SteamAPI_ISteamMatchmakingServers_RequestHistoryServerList(raw, appid, f, n, r)
+				},
+				ServerList::Spectator => {
+					SteamAPI_ISteamMatchmakingServers_RequestSpectatorServerList(
+						raw, appid, f, n, r,
+					)
+				},
+			}
+		};
+		if request.0.is_null() {
+			return None;
+		}
+
+		Some(ServerListStream {
+			servers: self,
+			request,
+			response,
+			_filters: filters,
+		})
+	}
+
+	/// Queries a single server through its query port.
+	pub fn ping_server(
+		&'a self,
+		addr: SocketAddrV4,
+	) -> Option<impl Future<Item = ServerInfo, Error = Error> + 'a> {
+		let response = Box::new(PingResponse {
+			vtable: &PING_VTABLE,
+			result: RefCell::new(None),
+		});
+		let handle = unsafe {
+			SteamAPI_ISteamMatchmakingServers_PingServer(
+				self.raw.clone(),
+				(*addr.ip()).into(),
+				addr.port(),
+				&*response as *const PingResponse as *const c_void,
+			)
+		};
+		if handle == INVALID_QUERY {
+			return None;
+		}
+
+		Some(ServerQuery {
+			servers: self,
+			handle,
+			response,
+			done: false,
+		})
+	}
+
+	/// Queries the players on a server through its query port.
+	pub fn server_players(
+		&'a self,
+		addr: SocketAddrV4,
+	) -> Option<impl Future<Item = Vec<PlayerInfo>, Error = Error> + 'a> {
+		let response = Box::new(PlayersResponse {
+			vtable:  &PLAYERS_VTABLE,
+			players: RefCell::new(Vec::new()),
+			result:  Cell::new(None),
+		});
+		let handle = unsafe {
+			SteamAPI_ISteamMatchmakingServers_PlayerDetails(
+				self.raw.clone(),
+				(*addr.ip()).into(),
+				addr.port(),
+				&*response as *const PlayersResponse as *const c_void,
+			)
+		};
+		if handle == INVALID_QUERY {
+			return None;
+		}
+
+		Some(ServerQuery {
+			servers: self,
+			handle,
+			response,
+			done: false,
+		})
+	}
+
+	/// Queries the rules, i.e. the server variables, of a server through its query port.
+	pub fn server_rules(
+		&'a self,
+		addr: SocketAddrV4,
+	) -> Option<impl Future<Item = Vec<(String, String)>, Error = Error> + 'a> {
+		let response = Box::new(RulesResponse {
+			vtable: &RULES_VTABLE,
+			rules:  RefCell::new(Vec::new()),
+			result: Cell::new(None),
+		});
+		let handle = unsafe {
+			SteamAPI_ISteamMatchmakingServers_ServerRules(
+				self.raw.clone(),
+				(*addr.ip()).into(),
+				addr.port(),
+				&*response as *const RulesResponse as *const c_void,
+			)
+		};
+		if handle == INVALID_QUERY {
+			return None;
+		}
+
+		Some(ServerQuery {
+			servers: self,
+			handle,
+			response,
+			done: false,
+		})
+	}
+}
+
+steam_extern! {
+	fn SteamAPI_ISteamClient_GetISteamMatchmakingServers<'a>(a: Raw<Client<'a>>, b: User<'a>, c: Pipe<'a>, d: *const c_char) -> MaybeRaw<MatchmakingServers<'a>>;
+
+	fn SteamAPI_ISteamMatchmakingServers_RequestInternetServerList(a: Raw<MatchmakingServers<'_>>, b: u32, c: *mut *const KeyValuePair, d: u32, e: *const c_void)   -> ServerListRequest;
+	fn SteamAPI_ISteamMatchmakingServers_RequestLANServerList(a: Raw<MatchmakingServers<'_>>, b: u32, c: *const c_void)                                               -> ServerListRequest;
+	fn SteamAPI_ISteamMatchmakingServers_RequestFriendsServerList(a: Raw<MatchmakingServers<'_>>, b: u32, c: *mut *const KeyValuePair, d: u32, e: *const c_void)    -> ServerListRequest;
+	fn SteamAPI_ISteamMatchmakingServers_RequestFavoritesServerList(a: Raw<MatchmakingServers<'_>>, b: u32, c: *mut *const KeyValuePair, d: u32, e: *const c_void)  -> ServerListRequest;
+	fn SteamAPI_ISteamMatchmakingServers_RequestHistoryServerList(a: Raw<MatchmakingServers<'_>>, b: u32, c: *mut *const KeyValuePair, d: u32, e: *const c_void)    -> ServerListRequest;
+	fn SteamAPI_ISteamMatchmakingServers_RequestSpectatorServerList(a: Raw<MatchmakingServers<'_>>, b: u32, c: *mut *const KeyValuePair, d: u32, e: *const c_void)  -> ServerListRequest;
+	fn SteamAPI_ISteamMatchmakingServers_ReleaseRequest(a: Raw<MatchmakingServers<'_>>, b: ServerListRequest);
+	fn SteamAPI_ISteamMatchmakingServers_GetServerDetails(a: Raw<MatchmakingServers<'_>>, b: ServerListRequest, c: i32) -> *const RawServerInfo;
+
+	fn SteamAPI_ISteamMatchmakingServers_PingServer(a: Raw<MatchmakingServers<'_>>, b: u32, c: u16, d: *const c_void)       -> ServerQueryHandle;
+	fn SteamAPI_ISteamMatchmakingServers_PlayerDetails(a: Raw<MatchmakingServers<'_>>, b: u32, c: u16, d: *const c_void) -> ServerQueryHandle;
+	fn SteamAPI_ISteamMatchmakingServers_ServerRules(a: Raw<MatchmakingServers<'_>>, b: u32, c: u16, d: *const c_void)      -> ServerQueryHandle;
+	fn SteamAPI_ISteamMatchmakingServers_CancelServerQuery(a: Raw<MatchmakingServers<'_>>, b: ServerQueryHandle);
+}
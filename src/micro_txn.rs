@@ -0,0 +1,100 @@
+use std::collections::{hash_set, HashSet};
+
+use futures::{Future, Stream};
+
+use crate::{Error, SteamUser};
+
+/// The user's answer to the purchase dialog of a microtransaction started by the backend.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MicroTxnAuthorization {
+	pub app_id:     u32,
+	pub order_id:   u64,
+	pub authorized: bool,
+}
+
+declare_notification! {
+	Data (152) {
+		app_id:     u32,
+		order_id:   u64,
+		authorized: u8,
+	} -> MicroTxnAuthorization;
+
+	map(
+		|Data {
+			app_id,
+			order_id,
+			authorized,
+		}| MicroTxnAuthorization {
+			app_id,
+			order_id,
+			authorized: authorized != 0,
+		}
+	);
+}
+
+/// The orders started through `ISteamMicroTxn/InitTxn` that are still waiting for the user.
+#[derive(Clone, Debug, Default)]
+pub struct PendingOrders {
+	orders: HashSet<u64>,
+}
+
+impl PendingOrders {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Starts tracking an order, returning `false` if it already was.
+	pub fn insert(&mut self, order_id: u64) -> bool {
+		self.orders.insert(order_id)
+	}
+
+	/// Stops tracking an order, e.g. once it timed out on the backend.
+	pub fn remove(&mut self, order_id: u64) -> bool {
+		self.orders.remove(&order_id)
+	}
+
+	pub fn contains(&self, order_id: u64) -> bool {
+		self.orders.contains(&order_id)
+	}
+
+	pub fn len(&self) -> usize {
+		self.orders.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.orders.is_empty()
+	}
+
+	pub fn iter(&self) -> hash_set::Iter<'_, u64> {
+		self.orders.iter()
+	}
+
+	/// Settles the order an authorization is for, returning `false` if it was not pending.
+	///
+	/// An authorized order still has to be finalized with `ISteamMicroTxn/FinalizeTxn`.
+	pub fn resolve(&mut self, authorization: &MicroTxnAuthorization) -> bool {
+		self.orders.remove(&authorization.order_id)
+	}
+}
+
+impl<'a> SteamUser<'a> {
+	/// Streams the user's answers to every purchase dialog.
+	pub fn micro_txn_authorizations(
+		&self,
+	) -> impl Stream<Item = MicroTxnAuthorization, Error = Error> + 'a {
+		self.callbacks.subscribe()
+	}
+
+	/// Waits for the user to answer the purchase dialog of an order, resolving to whether they
+	/// authorized it.
+	pub fn micro_txn_authorization(
+		&self,
+		order_id: u64,
+	) -> impl Future<Item = bool, Error = Error> + 'a {
+		self.micro_txn_authorizations()
+			.filter(move |authorization| authorization.order_id == order_id)
+			.into_future()
+			.map_err(|(e, _)| e)
+			.and_then(|(authorization, _)| Ok(authorization.ok_or(Error::Fail)?.authorized))
+	}
+}
@@ -0,0 +1,343 @@
+use derive_more::{Display, LowerHex, UpperHex};
+use std::{
+	ffi::{c_void, CStr},
+	net::{Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
+	ops::BitOr,
+	slice,
+};
+
+use futures::{Future, Stream};
+
+use crate::{
+	ConnectionInfo,
+	ConnectionState,
+	ConnectionStatus,
+	ConnectionStatusChanged,
+	Error,
+	ListenSocket,
+	PollGroup,
+	SessionFailed,
+	SessionRequest,
+	SteamId,
+};
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Hash, UpperHex, LowerHex)]
+pub struct NetConnection(pub u32);
+
+/// How a message is delivered.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SendFlags(pub i32);
+
+impl SendFlags {
+	pub const AUTO_RESTART_BROKEN_SESSION: SendFlags = SendFlags(32);
+	pub const NO_DELAY: SendFlags = SendFlags(4);
+	pub const NO_NAGLE: SendFlags = SendFlags(1);
+	pub const RELIABLE: SendFlags = SendFlags(8);
+	pub const UNRELIABLE: SendFlags = SendFlags(0);
+	pub const USE_CURRENT_THREAD: SendFlags = SendFlags(16);
+
+	pub fn contains(self, other: SendFlags) -> bool {
+		self.0 & other.0 == other.0
+	}
+}
+
+impl BitOr for SendFlags {
+	type Output = SendFlags;
+
+	fn bitor(self, other: SendFlags) -> SendFlags {
+		SendFlags(self.0 | other.0)
+	}
+}
+
+/// A peer on the network.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum NetworkingIdentity {
+	Invalid,
+	SteamId(SteamId),
+	Ip(SocketAddr),
+	/// An application-defined name, at most 31 bytes long.
+	GenericString(String),
+	/// Application-defined bytes, at most 32 of them.
+	GenericBytes(Vec<u8>),
+}
+
+impl From<SteamId> for NetworkingIdentity {
+	fn from(id: SteamId) -> Self {
+		NetworkingIdentity::SteamId(id)
+	}
+}
+
+impl From<SocketAddr> for NetworkingIdentity {
+	fn from(addr: SocketAddr) -> Self {
+		NetworkingIdentity::Ip(addr)
+	}
+}
+
+#[repr(C, packed)]
+#[derive(Clone, Copy)]
+pub(crate) struct RawIpAddr {
+	ipv6: [u8; 16],
+	port: u16,
+}
+
+impl From<SocketAddr> for RawIpAddr {
+	fn from(addr: SocketAddr) -> Self {
+		let ipv6 = match addr {
+			SocketAddr::V4(addr) => addr.ip().to_ipv6_mapped(),
+			SocketAddr::V6(addr) => *addr.ip(),
+		};
+		RawIpAddr {
+			ipv6: ipv6.octets(),
+			port: addr.port(),
+		}
+	}
+}
+
+impl From<RawIpAddr> for SocketAddr {
+	fn from(raw: RawIpAddr) -> Self {
+		let ip = Ipv6Addr::from(raw.ipv6);
+		match ip.to_ipv4_mapped() {
+			Some(ip) => SocketAddrV4::new(ip, raw.port).into(),
+			None => SocketAddrV6::new(ip, raw.port, 0, 0).into(),
+		}
+	}
+}
+
+#[repr(C, packed)]
+#[derive(Clone, Copy)]
+pub(crate) struct RawNetworkingIdentity {
+	kind: u32,
+	size: i32,
+	data: [u8; 128],
+}
+
+impl From<&NetworkingIdentity> for RawNetworkingIdentity {
+	fn from(identity: &NetworkingIdentity) -> Self {
+		let mut raw = RawNetworkingIdentity {
+			kind: 0,
+			size: 0,
+			data: [0; 128],
+		};
+		match identity {
+			NetworkingIdentity::Invalid => {},
+			NetworkingIdentity::SteamId(id) => {
+				raw.kind = 16;
+				raw.size = 8;
+				raw.data[..8].copy_from_slice(&id.0.to_ne_bytes());
+			},
+			NetworkingIdentity::Ip(addr) => {
+				let addr = RawIpAddr::from(*addr);
+				raw.kind = 1;
+				raw.size = 18;
+				raw.data[..16].copy_from_slice(&addr.ipv6);
+				raw.data[16..18].copy_from_slice(&{ addr.port }.to_ne_bytes());
+			},
+			NetworkingIdentity::GenericString(name) => {
+				let mut len = name.len().min(31);
+				while !name.is_char_boundary(len) {
+					len -= 1;
+				}
+				let name = &name.as_bytes()[..len];
+				raw.kind = 2;
+				raw.size = name.len() as i32 + 1;
+				raw.data[..name.len()].copy_from_slice(name);
+			},
+			NetworkingIdentity::GenericBytes(bytes) => {
+				let bytes = &bytes[..bytes.len().min(32)];
+				raw.kind = 3;
+				raw.size = bytes.len() as i32;
+				raw.data[..bytes.len()].copy_from_slice(bytes);
+			},
+		}
+		raw
+	}
+}
+
+impl From<RawNetworkingIdentity> for NetworkingIdentity {
+	fn from(raw: RawNetworkingIdentity) -> Self {
+		let data = raw.data;
+		match raw.kind {
+			16 => {
+				let mut id = [0; 8];
+				id.copy_from_slice(&data[..8]);
+				NetworkingIdentity::SteamId(SteamId(u64::from_ne_bytes(id)))
+			},
+			1 => {
+				let mut addr = RawIpAddr {
+					ipv6: [0; 16],
+					port: 0,
+				};
+				addr.ipv6.copy_from_slice(&data[..16]);
+				addr.port = u16::from_ne_bytes([data[16], data[17]]);
+				NetworkingIdentity::Ip(addr.into())
+			},
+			2 => {
+				let len = data.iter().take(32).position(|&b| b == 0).unwrap_or(32);
+				NetworkingIdentity::GenericString(
+					String::from_utf8_lossy(&data[..len]).into_owned(),
+				)
+			},
+			3 => {
+				let len = (raw.size.max(0) as usize).min(32);
+				NetworkingIdentity::GenericBytes(data[..len].to_vec())
+			},
+			_ => NetworkingIdentity::Invalid,
+		}
+	}
+}
+
+#[repr(C)]
+pub(crate) struct RawNetworkingMessage {
+	data:                 *mut c_void,
+	size:                 i32,
+	connection:           NetConnection,
+	peer:                 RawNetworkingIdentity,
+	connection_user_data: i64,
+	_time_received:       i64,
+	message_number:       i64,
+	_free_data:           *mut c_void,
+	_release:             *mut c_void,
+	channel:              i32,
+	flags:                SendFlags,
+	_user_data:           i64,
+	_lane:                u16,
+	_pad:                 u16,
+}
+
+/// A received message, copied out of Steam's buffer.
+#[derive(Clone, Debug)]
+pub struct NetworkingMessage {
+	pub data:                 Vec<u8>,
+	pub connection:           NetConnection,
+	pub peer:                 NetworkingIdentity,
+	pub connection_user_data: i64,
+	pub message_number:       i64,
+	pub channel:              i32,
+	pub flags:                SendFlags,
+}
+
+impl NetworkingMessage {
+	/// Copies `raw` and releases it.
+	pub(crate) unsafe fn from_raw(raw: *mut RawNetworkingMessage) -> Self {
+		let message = {
+			let raw = &*raw;
+			let data = if raw.data.is_null() {
+				Vec::new()
+			} else {
+				slice::from_raw_parts(raw.data as *const u8, raw.size.max(0) as usize).to_vec()
+			};
+			NetworkingMessage {
+				data,
+				connection: raw.connection,
+				peer: raw.peer.into(),
+				connection_user_data: raw.connection_user_data,
+				message_number: raw.message_number,
+				channel: raw.channel,
+				flags: raw.flags,
+			}
+		};
+		SteamAPI_SteamNetworkingMessage_t_Release(raw);
+		message
+	}
+}
+
+/// Connections to peers over virtual ports or addresses, as offered by `NetworkingSockets`.
+///
+/// Netcode written against this runs unchanged over Steam and over a `LoopbackPeer`.
+pub trait ConnectionTransport {
+	fn listen_p2p(&self, virtual_port: i32) -> Option<ListenSocket>;
+	fn listen_ip(&self, addr: SocketAddr) -> Option<ListenSocket>;
+	fn close_listen_socket(&self, socket: ListenSocket) -> Result<(), ()>;
+	fn connect_p2p(
+		&self,
+		identity: &NetworkingIdentity,
+		virtual_port: i32,
+	) -> Option<NetConnection>;
+	fn connect_ip(&self, addr: SocketAddr) -> Option<NetConnection>;
+	/// Incoming connections on `socket`, to be accepted with `accept_connection` or closed.
+	fn connection_requests(
+		&self,
+		socket: ListenSocket,
+	) -> Box<dyn Stream<Item = NetConnection, Error = Error> + '_>;
+	fn accept_connection(&self, connection: NetConnection) -> Result<(), Error>;
+	fn close_connection(
+		&self,
+		connection: NetConnection,
+		reason: i32,
+		debug: Option<&CStr>,
+		linger: bool,
+	) -> Result<(), ()>;
+	fn set_connection_user_data(&self, connection: NetConnection, data: i64) -> Result<(), ()>;
+	fn connection_info(&self, connection: NetConnection) -> Option<ConnectionInfo>;
+	fn connection_status(&self, connection: NetConnection) -> Result<ConnectionStatus, Error>;
+	fn connection_status_changes(
+		&self,
+	) -> Box<dyn Stream<Item = ConnectionStatusChanged, Error = Error> + '_>;
+	/// Resolves once `connection` is connected, or fails if it is closed first.
+	fn connected(
+		&self,
+		connection: NetConnection,
+	) -> Box<dyn Future<Item = (), Error = Error> + '_>;
+
+	fn connection_state(&self, connection: NetConnection) -> ConnectionState {
+		self.connection_info(connection)
+			.map_or(ConnectionState::None, |info| info.state)
+	}
+
+	fn send_message(
+		&self,
+		connection: NetConnection,
+		data: &[u8],
+		flags: SendFlags,
+	) -> Result<i64, Error>;
+	fn flush_messages(&self, connection: NetConnection) -> Result<(), Error>;
+	fn receive_messages(&self, connection: NetConnection, max: usize) -> Vec<NetworkingMessage>;
+
+	fn create_poll_group(&self) -> Option<PollGroup>;
+	fn destroy_poll_group(&self, group: PollGroup) -> Result<(), ()>;
+	/// Moves a connection into `group`, or out of its poll group if `None`.
+	fn set_connection_poll_group(
+		&self,
+		connection: NetConnection,
+		group: Option<PollGroup>,
+	) -> Result<(), ()>;
+	fn receive_messages_on_poll_group(
+		&self,
+		group: PollGroup,
+		max: usize,
+	) -> Vec<NetworkingMessage>;
+}
+
+/// Messages to peers on numbered channels without managing connections, as offered by
+/// `NetworkingMessages`.
+pub trait MessageTransport {
+	fn send_message(
+		&self,
+		remote: &NetworkingIdentity,
+		channel: i32,
+		data: &[u8],
+		flags: SendFlags,
+	) -> Result<(), Error>;
+	fn receive_messages(&self, channel: i32, max: usize) -> Vec<NetworkingMessage>;
+	fn channel_messages(
+		&self,
+		channel: i32,
+	) -> Box<dyn Stream<Item = NetworkingMessage, Error = Error> + '_>;
+	fn session_requests(&self) -> Box<dyn Stream<Item = SessionRequest, Error = Error> + '_>;
+	fn session_failures(&self) -> Box<dyn Stream<Item = SessionFailed, Error = Error> + '_>;
+	fn accept_session(&self, remote: &NetworkingIdentity) -> Result<(), ()>;
+	fn close_session(&self, remote: &NetworkingIdentity) -> Result<(), ()>;
+	/// Closes a single channel of a session, closing the session once no channel is left open.
+	fn close_channel(&self, remote: &NetworkingIdentity, channel: i32) -> Result<(), ()>;
+	/// The state of the session with a peer, and its details if there is one.
+	fn session_info(
+		&self,
+		remote: &NetworkingIdentity,
+	) -> (ConnectionState, Option<ConnectionInfo>);
+}
+
+steam_extern! {
+	fn SteamAPI_SteamNetworkingMessage_t_Release(a: *mut RawNetworkingMessage);
+}
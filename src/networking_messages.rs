@@ -0,0 +1,271 @@
+use const_cstr::const_cstr;
+use std::{collections::VecDeque, mem::MaybeUninit, ptr};
+
+use futures::{task, Async, Poll, Stream};
+
+use crate::{
+	Client,
+	ConnectionInfo,
+	ConnectionState,
+	Error,
+	MessageTransport,
+	NetworkingIdentity,
+	NetworkingMessage,
+	Raw,
+	RawConnectionInfo,
+	RawNetworkingIdentity,
+	RawNetworkingMessage,
+	RawResult,
+	SendFlags,
+};
+
+/// How many messages a `ChannelMessages` stream fetches at once.
+const RECEIVE_BATCH: usize = 32;
+
+/// A peer wants to start a session; accept it with `accept_session` to receive its messages.
+#[derive(Clone, Debug)]
+pub struct SessionRequest {
+	pub remote: NetworkingIdentity,
+}
+
+declare_notification! {
+	Data (1251) {
+		remote: RawNetworkingIdentity,
+	} -> SessionRequest;
+
+	map(|Data {remote}| SessionRequest {remote: remote.into()});
+}
+
+/// A session failed, e.g. because the peer could not be reached.
+#[derive(Clone, Debug)]
+pub struct SessionFailed {
+	pub info: ConnectionInfo,
+}
+
+declare_notification! {
+	Data (1252) {
+		info: RawConnectionInfo,
+	} -> SessionFailed;
+
+	map(|Data {info}| SessionFailed {info: info.into()});
+}
+
+/// The messages arriving on a channel, from any peer.
+pub struct ChannelMessages<'a> {
+	messages: NetworkingMessages<'a>,
+	channel:  i32,
+	pending:  VecDeque<NetworkingMessage>,
+}
+
+impl Stream for ChannelMessages<'_> {
+	type Error = Error;
+	type Item = NetworkingMessage;
+
+	fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+		if self.pending.is_empty() {
+			self.pending
+				.extend(self.messages.receive_messages(self.channel, RECEIVE_BATCH));
+		}
+
+		match self.pending.pop_front() {
+			Some(message) => Ok(Async::Ready(Some(message))),
+			None => {
+				task::current().notify();
+				Ok(Async::NotReady)
+			},
+		}
+	}
+}
+
+interface!(NetworkingMessages);
+impl<'a> NetworkingMessages<'a> {
+	pub fn new(client: &Client<'a>) -> Option<Self> {
+		let raw = client.generic_interface(const_cstr!("SteamNetworkingMessages002").as_cstr())?;
+		let utils = client.utils.clone();
+		let callbacks = client.callbacks.clone();
+
+		Some(NetworkingMessages {
+			raw,
+			utils,
+			callbacks,
+		})
+	}
+
+	/// Sends a message to `channel` of a peer, starting a session with it if needed.
+	pub fn send_message(
+		&self,
+		remote: &NetworkingIdentity,
+		channel: i32,
+		data: &[u8],
+		flags: SendFlags,
+	) -> Result<(), Error> {
+		let remote = RawNetworkingIdentity::from(remote);
+		unsafe {
+			SteamAPI_ISteamNetworkingMessages_SendMessageToUser(
+				self.raw.clone(),
+				&remote,
+				data.as_ptr(),
+				data.len() as u32,
+				flags,
+				channel,
+			)
+			.into()
+		}
+	}
+
+	/// Receives up to `max` pending messages on `channel`.
+	pub fn receive_messages(&self, channel: i32, max: usize) -> Vec<NetworkingMessage> {
+		let mut messages = vec![ptr::null_mut(); max];
+		let count = unsafe {
+			SteamAPI_ISteamNetworkingMessages_ReceiveMessagesOnChannel(
+				self.raw.clone(),
+				channel,
+				messages.as_mut_ptr(),
+				max as i32,
+			)
+		};
+		messages.truncate(count.max(0) as usize);
+		messages
+			.into_iter()
+			.map(|message| unsafe { NetworkingMessage::from_raw(message) })
+			.collect()
+	}
+
+	/// Streams the messages arriving on `channel`.
+	pub fn channel_messages(&self, channel: i32) -> ChannelMessages<'a> {
+		ChannelMessages {
+			messages: self.clone(),
+			channel,
+			pending: VecDeque::new(),
+		}
+	}
+
+	pub fn accept_session(&self, remote: &NetworkingIdentity) -> Result<(), ()> {
+		let remote = RawNetworkingIdentity::from(remote);
+		if unsafe {
+			SteamAPI_ISteamNetworkingMessages_AcceptSessionWithUser(self.raw.clone(), &remote)
+		} {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	pub fn close_session(&self, remote: &NetworkingIdentity) -> Result<(), ()> {
+		let remote = RawNetworkingIdentity::from(remote);
+		if unsafe {
+			SteamAPI_ISteamNetworkingMessages_CloseSessionWithUser(self.raw.clone(), &remote)
+		} {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	/// Closes a single channel of a session, closing the session once no channel is left open.
+	pub fn close_channel(&self, remote: &NetworkingIdentity, channel: i32) -> Result<(), ()> {
+		let remote = RawNetworkingIdentity::from(remote);
+		if unsafe {
+			SteamAPI_ISteamNetworkingMessages_CloseChannelWithUser(
+				self.raw.clone(),
+				&remote,
+				channel,
+			)
+		} {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	/// The state of the session with a peer, and its details if there is one.
+	pub fn session_info(
+		&self,
+		remote: &NetworkingIdentity,
+	) -> (ConnectionState, Option<ConnectionInfo>) {
+		let remote = RawNetworkingIdentity::from(remote);
+		let mut info = MaybeUninit::<RawConnectionInfo>::uninit();
+		let state: ConnectionState = unsafe {
+			SteamAPI_ISteamNetworkingMessages_GetSessionConnectionInfo(
+				self.raw.clone(),
+				&remote,
+				info.as_mut_ptr(),
+				ptr::null_mut(),
+			)
+		}
+		.into();
+
+		match state {
+			ConnectionState::None => (state, None),
+			_ => (state, Some(unsafe { info.assume_init() }.into())),
+		}
+	}
+
+	pub fn session_requests(&self) -> impl Stream<Item = SessionRequest, Error = Error> + 'a {
+		self.callbacks.subscribe()
+	}
+
+	pub fn session_failures(&self) -> impl Stream<Item = SessionFailed, Error = Error> + 'a {
+		self.callbacks.subscribe()
+	}
+}
+
+impl MessageTransport for NetworkingMessages<'_> {
+	fn send_message(
+		&self,
+		remote: &NetworkingIdentity,
+		channel: i32,
+		data: &[u8],
+		flags: SendFlags,
+	) -> Result<(), Error> {
+		NetworkingMessages::send_message(self, remote, channel, data, flags)
+	}
+
+	fn receive_messages(&self, channel: i32, max: usize) -> Vec<NetworkingMessage> {
+		NetworkingMessages::receive_messages(self, channel, max)
+	}
+
+	fn channel_messages(
+		&self,
+		channel: i32,
+	) -> Box<dyn Stream<Item = NetworkingMessage, Error = Error> + '_> {
+		Box::new(NetworkingMessages::channel_messages(self, channel))
+	}
+
+	fn session_requests(&self) -> Box<dyn Stream<Item = SessionRequest, Error = Error> + '_> {
+		Box::new(NetworkingMessages::session_requests(self))
+	}
+
+	fn session_failures(&self) -> Box<dyn Stream<Item = SessionFailed, Error = Error> + '_> {
+		Box::new(NetworkingMessages::session_failures(self))
+	}
+
+	fn accept_session(&self, remote: &NetworkingIdentity) -> Result<(), ()> {
+		NetworkingMessages::accept_session(self, remote)
+	}
+
+	fn close_session(&self, remote: &NetworkingIdentity) -> Result<(), ()> {
+		NetworkingMessages::close_session(self, remote)
+	}
+
+	fn close_channel(&self, remote: &NetworkingIdentity, channel: i32) -> Result<(), ()> {
+		NetworkingMessages::close_channel(self, remote, channel)
+	}
+
+	fn session_info(
+		&self,
+		remote: &NetworkingIdentity,
+	) -> (ConnectionState, Option<ConnectionInfo>) {
+		NetworkingMessages::session_info(self, remote)
+	}
+}
+
+steam_extern! {
+	fn SteamAPI_ISteamNetworkingMessages_SendMessageToUser(a: Raw<NetworkingMessages<'_>>, b: *const RawNetworkingIdentity, c: *const u8, d: u32, e: SendFlags, f: i32) -> RawResult;
+	fn SteamAPI_ISteamNetworkingMessages_ReceiveMessagesOnChannel(a: Raw<NetworkingMessages<'_>>, b: i32, c: *mut *mut RawNetworkingMessage, d: i32)                  -> i32;
+
+	fn SteamAPI_ISteamNetworkingMessages_AcceptSessionWithUser(a: Raw<NetworkingMessages<'_>>, b: *const RawNetworkingIdentity)          -> bool;
+	fn SteamAPI_ISteamNetworkingMessages_CloseSessionWithUser(a: Raw<NetworkingMessages<'_>>, b: *const RawNetworkingIdentity)           -> bool;
+	fn SteamAPI_ISteamNetworkingMessages_CloseChannelWithUser(a: Raw<NetworkingMessages<'_>>, b: *const RawNetworkingIdentity, c: i32) -> bool;
+	fn SteamAPI_ISteamNetworkingMessages_GetSessionConnectionInfo(a: Raw<NetworkingMessages<'_>>, b: *const RawNetworkingIdentity, c: *mut RawConnectionInfo, d: *mut u8) -> i32;
+}
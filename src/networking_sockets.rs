@@ -0,0 +1,614 @@
+use const_cstr::const_cstr;
+use derive_more::{Display, LowerHex, UpperHex};
+use std::{ffi::CStr, net::SocketAddr, os::raw::c_char, ptr, time::Duration};
+
+use futures::{Future, Stream};
+
+use crate::{
+	string_from_ptr,
+	Client,
+	ConnectionTransport,
+	Error,
+	NetConnection,
+	NetworkingIdentity,
+	NetworkingMessage,
+	Raw,
+	RawIpAddr,
+	RawNetworkingIdentity,
+	RawNetworkingMessage,
+	RawResult,
+	SendFlags,
+};
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Hash, UpperHex, LowerHex)]
+pub struct ListenSocket(pub u32);
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Hash, UpperHex, LowerHex)]
+pub struct PollGroup(pub u32);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ConnectionState {
+	None,
+	Connecting,
+	FindingRoute,
+	Connected,
+	ClosedByPeer,
+	ProblemDetectedLocally,
+}
+
+impl From<i32> for ConnectionState {
+	fn from(state: i32) -> Self {
+		match state {
+			1 => ConnectionState::Connecting,
+			2 => ConnectionState::FindingRoute,
+			3 => ConnectionState::Connected,
+			4 => ConnectionState::ClosedByPeer,
+			5 => ConnectionState::ProblemDetectedLocally,
+			_ => ConnectionState::None,
+		}
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConnectionInfo {
+	pub remote:        NetworkingIdentity,
+	pub user_data:     i64,
+	/// The listen socket the connection was accepted on, if any.
+	pub listen_socket: Option<ListenSocket>,
+	pub remote_addr:   Option<SocketAddr>,
+	pub state:         ConnectionState,
+	/// The `ESteamNetConnectionEnd` reason the connection was closed with.
+	pub end_reason:    i32,
+	pub end_debug:     String,
+	pub description:   String,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub(crate) struct RawConnectionInfo {
+	remote:        RawNetworkingIdentity,
+	user_data:     i64,
+	listen_socket: u32,
+	remote_addr:   RawIpAddr,
+	_pad:          u16,
+	_pop_remote:   u32,
+	_pop_relay:    u32,
+	state:         i32,
+	end_reason:    i32,
+	end_debug:     [c_char; 128],
+	description:   [c_char; 128],
+	_flags:        i32,
+	_reserved:     [u32; 63],
+}
+
+impl From<RawConnectionInfo> for ConnectionInfo {
+	fn from(raw: RawConnectionInfo) -> Self {
+		let remote_addr = SocketAddr::from(raw.remote_addr);
+		ConnectionInfo {
+			remote:        raw.remote.into(),
+			user_data:     raw.user_data,
+			listen_socket: Some(ListenSocket(raw.listen_socket)).filter(|s| s.0 != 0),
+			remote_addr:   Some(remote_addr).filter(|addr| !addr.ip().is_unspecified()),
+			state:         raw.state.into(),
+			end_reason:    raw.end_reason,
+			end_debug:     string_from_ptr(raw.end_debug.as_ptr()).unwrap_or_default(),
+			description:   string_from_ptr(raw.description.as_ptr()).unwrap_or_default(),
+		}
+	}
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConnectionStatus {
+	pub state:                 ConnectionState,
+	pub ping:                  Duration,
+	/// The fraction of packets delivered end-to-end, from 0 to 1, as measured locally.
+	pub quality_local:         f32,
+	/// The fraction of packets delivered end-to-end, from 0 to 1, as measured by the peer.
+	pub quality_remote:        f32,
+	pub out_packets_per_sec:   f32,
+	pub out_bytes_per_sec:     f32,
+	pub in_packets_per_sec:    f32,
+	pub in_bytes_per_sec:      f32,
+	pub send_rate:             u32,
+	pub pending_unreliable:    u32,
+	pub pending_reliable:      u32,
+	pub sent_unacked_reliable: u32,
+	/// How long a message sent now would wait in the queue.
+	pub queue_time:            Duration,
+}
+
+#[repr(C)]
+pub(crate) struct RawConnectionStatus {
+	state:                 i32,
+	ping:                  i32,
+	quality_local:         f32,
+	quality_remote:        f32,
+	out_packets_per_sec:   f32,
+	out_bytes_per_sec:     f32,
+	in_packets_per_sec:    f32,
+	in_bytes_per_sec:      f32,
+	send_rate:             i32,
+	pending_unreliable:    i32,
+	pending_reliable:      i32,
+	sent_unacked_reliable: i32,
+	queue_time:            i64,
+	_reserved:             [u32; 16],
+}
+
+#[derive(Clone, Debug)]
+pub struct ConnectionStatusChanged {
+	pub connection: NetConnection,
+	pub info:       ConnectionInfo,
+	pub old_state:  ConnectionState,
+}
+
+declare_notification! {
+	Data (1221) {
+		connection: NetConnection,
+		info:       RawConnectionInfo,
+		old_state:  i32,
+	} -> ConnectionStatusChanged;
+
+	map(|Data {connection, info, old_state}| ConnectionStatusChanged {
+		connection,
+		info: info.into(),
+		old_state: old_state.into(),
+	});
+}
+
+interface!(NetworkingSockets);
+impl<'a> NetworkingSockets<'a> {
+	pub fn new(client: &Client<'a>) -> Option<Self> {
+		let raw = client.generic_interface(const_cstr!("SteamNetworkingSockets012").as_cstr())?;
+		let utils = client.utils.clone();
+		let callbacks = client.callbacks.clone();
+
+		Some(NetworkingSockets {
+			raw,
+			utils,
+			callbacks,
+		})
+	}
+
+	/// Listens for P2P connections on `virtual_port`.
+	pub fn listen_p2p(&self, virtual_port: i32) -> Option<ListenSocket> {
+		let socket = unsafe {
+			SteamAPI_ISteamNetworkingSockets_CreateListenSocketP2P(
+				self.raw.clone(),
+				virtual_port,
+				0,
+				ptr::null(),
+			)
+		};
+		Some(socket).filter(|s| s.0 != 0)
+	}
+
+	/// Listens for direct UDP connections on `addr`.
+	pub fn listen_ip(&self, addr: SocketAddr) -> Option<ListenSocket> {
+		let addr = RawIpAddr::from(addr);
+		let socket = unsafe {
+			SteamAPI_ISteamNetworkingSockets_CreateListenSocketIP(
+				self.raw.clone(),
+				&addr,
+				0,
+				ptr::null(),
+			)
+		};
+		Some(socket).filter(|s| s.0 != 0)
+	}
+
+	pub fn close_listen_socket(&self, socket: ListenSocket) -> Result<(), ()> {
+		if unsafe { SteamAPI_ISteamNetworkingSockets_CloseListenSocket(self.raw.clone(), socket) } {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	/// Connects to a peer listening on `virtual_port`.
+	pub fn connect_p2p(
+		&self,
+		identity: &NetworkingIdentity,
+		virtual_port: i32,
+	) -> Option<NetConnection> {
+		let identity = RawNetworkingIdentity::from(identity);
+		let connection = unsafe {
+			SteamAPI_ISteamNetworkingSockets_ConnectP2P(
+				self.raw.clone(),
+				&identity,
+				virtual_port,
+				0,
+				ptr::null(),
+			)
+		};
+		Some(connection).filter(|c| c.0 != 0)
+	}
+
+	pub fn connect_ip(&self, addr: SocketAddr) -> Option<NetConnection> {
+		let addr = RawIpAddr::from(addr);
+		let connection = unsafe {
+			SteamAPI_ISteamNetworkingSockets_ConnectByIPAddress(
+				self.raw.clone(),
+				&addr,
+				0,
+				ptr::null(),
+			)
+		};
+		Some(connection).filter(|c| c.0 != 0)
+	}
+
+	/// Accepts an incoming connection, as announced by `connection_requests`.
+	pub fn accept_connection(&self, connection: NetConnection) -> Result<(), Error> {
+		unsafe {
+			SteamAPI_ISteamNetworkingSockets_AcceptConnection(self.raw.clone(), connection).into()
+		}
+	}
+
+	/// Closes a connection, sending any reliable messages still queued first if `linger` is set.
+	///
+	/// `reason` is an `ESteamNetConnectionEnd` code, shown to the peer along with `debug`.
+	pub fn close_connection(
+		&self,
+		connection: NetConnection,
+		reason: i32,
+		debug: Option<&CStr>,
+		linger: bool,
+	) -> Result<(), ()> {
+		if unsafe {
+			SteamAPI_ISteamNetworkingSockets_CloseConnection(
+				self.raw.clone(),
+				connection,
+				reason,
+				debug.map_or(ptr::null(), CStr::as_ptr),
+				linger,
+			)
+		} {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	pub fn set_connection_user_data(&self, connection: NetConnection, data: i64) -> Result<(), ()> {
+		if unsafe {
+			SteamAPI_ISteamNetworkingSockets_SetConnectionUserData(
+				self.raw.clone(),
+				connection,
+				data,
+			)
+		} {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	/// Sends a message, returning its message number.
+	pub fn send_message(
+		&self,
+		connection: NetConnection,
+		data: &[u8],
+		flags: SendFlags,
+	) -> Result<i64, Error> {
+		let mut message_number = 0;
+		let result = unsafe {
+			SteamAPI_ISteamNetworkingSockets_SendMessageToConnection(
+				self.raw.clone(),
+				connection,
+				data.as_ptr(),
+				data.len() as u32,
+				flags,
+				&mut message_number,
+			)
+		};
+		Result::from(result).map(|()| message_number)
+	}
+
+	/// Sends any messages held back by Nagle's algorithm.
+	pub fn flush_messages(&self, connection: NetConnection) -> Result<(), Error> {
+		unsafe {
+			SteamAPI_ISteamNetworkingSockets_FlushMessagesOnConnection(self.raw.clone(), connection)
+				.into()
+		}
+	}
+
+	/// Receives up to `max` pending messages on a connection.
+	pub fn receive_messages(
+		&self,
+		connection: NetConnection,
+		max: usize,
+	) -> Vec<NetworkingMessage> {
+		let mut messages = vec![ptr::null_mut(); max];
+		let count = unsafe {
+			SteamAPI_ISteamNetworkingSockets_ReceiveMessagesOnConnection(
+				self.raw.clone(),
+				connection,
+				messages.as_mut_ptr(),
+				max as i32,
+			)
+		};
+		messages.truncate(count.max(0) as usize);
+		messages
+			.into_iter()
+			.map(|message| unsafe { NetworkingMessage::from_raw(message) })
+			.collect()
+	}
+
+	pub fn create_poll_group(&self) -> Option<PollGroup> {
+		let group = unsafe { SteamAPI_ISteamNetworkingSockets_CreatePollGroup(self.raw.clone()) };
+		Some(group).filter(|g| g.0 != 0)
+	}
+
+	pub fn destroy_poll_group(&self, group: PollGroup) -> Result<(), ()> {
+		if unsafe { SteamAPI_ISteamNetworkingSockets_DestroyPollGroup(self.raw.clone(), group) } {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	/// Moves a connection into `group`, or out of its poll group if `None`.
+	pub fn set_connection_poll_group(
+		&self,
+		connection: NetConnection,
+		group: Option<PollGroup>,
+	) -> Result<(), ()> {
+		if unsafe {
+			SteamAPI_ISteamNetworkingSockets_SetConnectionPollGroup(
+				self.raw.clone(),
+				connection,
+				group.unwrap_or(PollGroup(0)),
+			)
+		} {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	/// Receives up to `max` pending messages on any connection of a poll group.
+	pub fn receive_messages_on_poll_group(
+		&self,
+		group: PollGroup,
+		max: usize,
+	) -> Vec<NetworkingMessage> {
+		let mut messages = vec![ptr::null_mut(); max];
+		let count = unsafe {
+			SteamAPI_ISteamNetworkingSockets_ReceiveMessagesOnPollGroup(
+				self.raw.clone(),
+				group,
+				messages.as_mut_ptr(),
+				max as i32,
+			)
+		};
+		messages.truncate(count.max(0) as usize);
+		messages
+			.into_iter()
+			.map(|message| unsafe { NetworkingMessage::from_raw(message) })
+			.collect()
+	}
+
+	pub fn connection_info(&self, connection: NetConnection) -> Option<ConnectionInfo> {
+		let mut info = std::mem::MaybeUninit::<RawConnectionInfo>::uninit();
+		if unsafe {
+			SteamAPI_ISteamNetworkingSockets_GetConnectionInfo(
+				self.raw.clone(),
+				connection,
+				info.as_mut_ptr(),
+			)
+		} {
+			Some(unsafe { info.assume_init() }.into())
+		} else {
+			None
+		}
+	}
+
+	pub fn connection_status(&self, connection: NetConnection) -> Result<ConnectionStatus, Error> {
+		let mut status = std::mem::MaybeUninit::<RawConnectionStatus>::uninit();
+		let result = unsafe {
+			SteamAPI_ISteamNetworkingSockets_GetConnectionRealTimeStatus(
+				self.raw.clone(),
+				connection,
+				status.as_mut_ptr(),
+				0,
+				ptr::null_mut(),
+			)
+		};
+		Result::from(result)?;
+
+		let status = unsafe { status.assume_init() };
+		Ok(ConnectionStatus {
+			state:                 status.state.into(),
+			ping:                  Duration::from_millis(status.ping.max(0) as u64),
+			quality_local:         status.quality_local,
+			quality_remote:        status.quality_remote,
+			out_packets_per_sec:   status.out_packets_per_sec,
+			out_bytes_per_sec:     status.out_bytes_per_sec,
+			in_packets_per_sec:    status.in_packets_per_sec,
+			in_bytes_per_sec:      status.in_bytes_per_sec,
+			send_rate:             status.send_rate.max(0) as u32,
+			pending_unreliable:    status.pending_unreliable.max(0) as u32,
+			pending_reliable:      status.pending_reliable.max(0) as u32,
+			sent_unacked_reliable: status.sent_unacked_reliable.max(0) as u32,
+			queue_time:            Duration::from_micros(status.queue_time.max(0) as u64),
+		})
+	}
+
+	pub fn connection_status_changes(
+		&self,
+	) -> impl Stream<Item = ConnectionStatusChanged, Error = Error> + 'a {
+		self.callbacks.subscribe()
+	}
+
+	/// Incoming connections on `socket`, to be accepted with `accept_connection` or closed.
+	pub fn connection_requests(
+		&self,
+		socket: ListenSocket,
+	) -> impl Stream<Item = NetConnection, Error = Error> + 'a {
+		self.connection_status_changes()
+			.filter(move |change| {
+				change.info.listen_socket == Some(socket)
+					&& change.old_state == ConnectionState::None
+					&& change.info.state == ConnectionState::Connecting
+			})
+			.map(|change| change.connection)
+	}
+
+	/// Resolves once `connection` is connected, or fails if it is closed first.
+	pub fn connected(
+		&self,
+		connection: NetConnection,
+	) -> impl Future<Item = (), Error = Error> + 'a {
+		self.connection_status_changes()
+			.filter(move |change| change.connection == connection)
+			.filter_map(|change| match change.info.state {
+				ConnectionState::Connected => Some(Ok(())),
+				ConnectionState::ClosedByPeer | ConnectionState::ProblemDetectedLocally => {
+					Some(Err(Error::NoConnection))
+				},
+				_ => None,
+			})
+			.into_future()
+			.map_err(|(e, _)| e)
+			.and_then(|(result, _)| result.unwrap_or(Err(Error::Fail)))
+	}
+}
+
+impl ConnectionTransport for NetworkingSockets<'_> {
+	fn listen_p2p(&self, virtual_port: i32) -> Option<ListenSocket> {
+		NetworkingSockets::listen_p2p(self, virtual_port)
+	}
+
+	fn listen_ip(&self, addr: SocketAddr) -> Option<ListenSocket> {
+		NetworkingSockets::listen_ip(self, addr)
+	}
+
+	fn close_listen_socket(&self, socket: ListenSocket) -> Result<(), ()> {
+		NetworkingSockets::close_listen_socket(self, socket)
+	}
+
+	fn connect_p2p(
+		&self,
+		identity: &NetworkingIdentity,
+		virtual_port: i32,
+	) -> Option<NetConnection> {
+		NetworkingSockets::connect_p2p(self, identity, virtual_port)
+	}
+
+	fn connect_ip(&self, addr: SocketAddr) -> Option<NetConnection> {
+		NetworkingSockets::connect_ip(self, addr)
+	}
+
+	fn connection_requests(
+		&self,
+		socket: ListenSocket,
+	) -> Box<dyn Stream<Item = NetConnection, Error = Error> + '_> {
+		Box::new(NetworkingSockets::connection_requests(self, socket))
+	}
+
+	fn accept_connection(&self, connection: NetConnection) -> Result<(), Error> {
+		NetworkingSockets::accept_connection(self, connection)
+	}
+
+	fn close_connection(
+		&self,
+		connection: NetConnection,
+		reason: i32,
+		debug: Option<&CStr>,
+		linger: bool,
+	) -> Result<(), ()> {
+		NetworkingSockets::close_connection(self, connection, reason, debug, linger)
+	}
+
+	fn set_connection_user_data(&self, connection: NetConnection, data: i64) -> Result<(), ()> {
+		NetworkingSockets::set_connection_user_data(self, connection, data)
+	}
+
+	fn connection_info(&self, connection: NetConnection) -> Option<ConnectionInfo> {
+		NetworkingSockets::connection_info(self, connection)
+	}
+
+	fn connection_status(&self, connection: NetConnection) -> Result<ConnectionStatus, Error> {
+		NetworkingSockets::connection_status(self, connection)
+	}
+
+	fn connection_status_changes(
+		&self,
+	) -> Box<dyn Stream<Item = ConnectionStatusChanged, Error = Error> + '_> {
+		Box::new(NetworkingSockets::connection_status_changes(self))
+	}
+
+	fn connected(
+		&self,
+		connection: NetConnection,
+	) -> Box<dyn Future<Item = (), Error = Error> + '_> {
+		Box::new(NetworkingSockets::connected(self, connection))
+	}
+
+	fn send_message(
+		&self,
+		connection: NetConnection,
+		data: &[u8],
+		flags: SendFlags,
+	) -> Result<i64, Error> {
+		NetworkingSockets::send_message(self, connection, data, flags)
+	}
+
+	fn flush_messages(&self, connection: NetConnection) -> Result<(), Error> {
+		NetworkingSockets::flush_messages(self, connection)
+	}
+
+	fn receive_messages(&self, connection: NetConnection, max: usize) -> Vec<NetworkingMessage> {
+		NetworkingSockets::receive_messages(self, connection, max)
+	}
+
+	fn create_poll_group(&self) -> Option<PollGroup> {
+		NetworkingSockets::create_poll_group(self)
+	}
+
+	fn destroy_poll_group(&self, group: PollGroup) -> Result<(), ()> {
+		NetworkingSockets::destroy_poll_group(self, group)
+	}
+
+	fn set_connection_poll_group(
+		&self,
+		connection: NetConnection,
+		group: Option<PollGroup>,
+	) -> Result<(), ()> {
+		NetworkingSockets::set_connection_poll_group(self, connection, group)
+	}
+
+	fn receive_messages_on_poll_group(
+		&self,
+		group: PollGroup,
+		max: usize,
+	) -> Vec<NetworkingMessage> {
+		NetworkingSockets::receive_messages_on_poll_group(self, group, max)
+	}
+}
+
+steam_extern! {
+	fn SteamAPI_ISteamNetworkingSockets_CreateListenSocketIP(a: Raw<NetworkingSockets<'_>>, b: *const RawIpAddr, c: i32, d: *const u8) -> ListenSocket;
+	fn SteamAPI_ISteamNetworkingSockets_CreateListenSocketP2P(a: Raw<NetworkingSockets<'_>>, b: i32, c: i32, d: *const u8)             -> ListenSocket;
+	fn SteamAPI_ISteamNetworkingSockets_CloseListenSocket(a: Raw<NetworkingSockets<'_>>, b: ListenSocket) -> bool;
+
+	fn SteamAPI_ISteamNetworkingSockets_ConnectByIPAddress(a: Raw<NetworkingSockets<'_>>, b: *const RawIpAddr, c: i32, d: *const u8)                      -> NetConnection;
+	fn SteamAPI_ISteamNetworkingSockets_ConnectP2P(a: Raw<NetworkingSockets<'_>>, b: *const RawNetworkingIdentity, c: i32, d: i32, e: *const u8) -> NetConnection;
+	fn SteamAPI_ISteamNetworkingSockets_AcceptConnection(a: Raw<NetworkingSockets<'_>>, b: NetConnection) -> RawResult;
+	fn SteamAPI_ISteamNetworkingSockets_CloseConnection(a: Raw<NetworkingSockets<'_>>, b: NetConnection, c: i32, d: *const c_char, e: bool) -> bool;
+	fn SteamAPI_ISteamNetworkingSockets_SetConnectionUserData(a: Raw<NetworkingSockets<'_>>, b: NetConnection, c: i64) -> bool;
+
+	fn SteamAPI_ISteamNetworkingSockets_SendMessageToConnection(a: Raw<NetworkingSockets<'_>>, b: NetConnection, c: *const u8, d: u32, e: SendFlags, f: *mut i64) -> RawResult;
+	fn SteamAPI_ISteamNetworkingSockets_FlushMessagesOnConnection(a: Raw<NetworkingSockets<'_>>, b: NetConnection)                                                  -> RawResult;
+	fn SteamAPI_ISteamNetworkingSockets_ReceiveMessagesOnConnection(a: Raw<NetworkingSockets<'_>>, b: NetConnection, c: *mut *mut RawNetworkingMessage, d: i32)     -> i32;
+
+	fn SteamAPI_ISteamNetworkingSockets_CreatePollGroup(a: Raw<NetworkingSockets<'_>>) -> PollGroup;
+	fn SteamAPI_ISteamNetworkingSockets_DestroyPollGroup(a: Raw<NetworkingSockets<'_>>, b: PollGroup) -> bool;
+	fn SteamAPI_ISteamNetworkingSockets_SetConnectionPollGroup(a: Raw<NetworkingSockets<'_>>, b: NetConnection, c: PollGroup) -> bool;
+	fn SteamAPI_ISteamNetworkingSockets_ReceiveMessagesOnPollGroup(a: Raw<NetworkingSockets<'_>>, b: PollGroup, c: *mut *mut RawNetworkingMessage, d: i32) -> i32;
+
+	fn SteamAPI_ISteamNetworkingSockets_GetConnectionInfo(a: Raw<NetworkingSockets<'_>>, b: NetConnection, c: *mut RawConnectionInfo) -> bool;
+	fn SteamAPI_ISteamNetworkingSockets_GetConnectionRealTimeStatus(a: Raw<NetworkingSockets<'_>>, b: NetConnection, c: *mut RawConnectionStatus, d: i32, e: *mut u8) -> RawResult;
+}
@@ -22,6 +22,11 @@ use crate::{
 #[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Hash, UpperHex, LowerHex)]
 pub struct Item(pub u64);
 
+/// A handle to user-generated content shared through remote storage.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Hash, UpperHex, LowerHex)]
+pub struct Ugc(pub u64);
+
 #[repr(transparent)]
 #[derive(Debug)]
 struct UpdateHandle<'a>(u64, PhantomData<&'a ()>);
@@ -53,8 +58,13 @@ impl<'a> RemoteStorage<'a> {
 			.check()?
 		};
 		let utils = client.utils.clone();
+		let callbacks = client.callbacks.clone();
 
-		Some(RemoteStorage { raw, utils })
+		Some(RemoteStorage {
+			raw,
+			utils,
+			callbacks,
+		})
 	}
 
 	pub fn file_write(
@@ -0,0 +1,182 @@
+use const_cstr::const_cstr;
+use derive_more::Display;
+use std::{ffi::CStr, os::raw::c_char, ptr};
+
+use futures::Stream;
+
+use crate::{Client, Error, Item, MaybeRaw, Pipe, Raw, RawResult, SteamId, User};
+
+/// A screenshot in the user's library.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Hash)]
+pub struct ScreenshotHandle(pub u32);
+
+const INVALID_SCREENSHOT: ScreenshotHandle = ScreenshotHandle(0);
+
+/// A screenshot was written to the library, or failed to be.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScreenshotReady {
+	pub handle: ScreenshotHandle,
+	pub result: Result<(), Error>,
+}
+
+declare_notification! {
+	Data (2301) {
+		handle: ScreenshotHandle,
+		result: RawResult,
+	} -> ScreenshotReady;
+
+	map(|Data {handle, result}| ScreenshotReady {handle, result: result.into()});
+}
+
+/// The user asked for a screenshot while they are hooked, so the game should take one itself.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScreenshotRequested;
+
+declare_notification! {
+	Data (2302) {} -> ScreenshotRequested;
+
+	map(|Data {}| ScreenshotRequested);
+}
+
+interface!(Screenshots);
+impl<'a> Screenshots<'a> {
+	pub fn new(client: &Client<'a>) -> Option<Self> {
+		let raw = unsafe {
+			SteamAPI_ISteamClient_GetISteamScreenshots(
+				client.raw.clone(),
+				client.user(),
+				client.pipe(),
+				const_cstr!("STEAMSCREENSHOTS_INTERFACE_VERSION003").as_ptr(),
+			)
+			.check()?
+		};
+		let utils = client.utils.clone();
+		let callbacks = client.callbacks.clone();
+
+		Some(Screenshots {
+			raw,
+			utils,
+			callbacks,
+		})
+	}
+
+	/// Adds a screenshot to the library from tightly packed rows of RGB pixels.
+	pub fn write_screenshot(
+		&self,
+		rgb: &[u8],
+		width: u32,
+		height: u32,
+	) -> Option<ScreenshotHandle> {
+		if rgb.len() != width as usize * height as usize * 3 {
+			return None;
+		}
+
+		let handle = unsafe {
+			SteamAPI_ISteamScreenshots_WriteScreenshot(
+				self.raw.clone(),
+				rgb.as_ptr(),
+				rgb.len() as u32,
+				width as i32,
+				height as i32,
+			)
+		};
+		if handle == INVALID_SCREENSHOT {
+			None
+		} else {
+			Some(handle)
+		}
+	}
+
+	/// Adds a JPEG, TGA or PNG file to the library, with a thumbnail of the given size made if
+	/// `thumbnail` is `None`.
+	pub fn add_screenshot_to_library(
+		&self,
+		file: &CStr,
+		thumbnail: Option<&CStr>,
+		width: u32,
+		height: u32,
+	) -> Option<ScreenshotHandle> {
+		let handle = unsafe {
+			SteamAPI_ISteamScreenshots_AddScreenshotToLibrary(
+				self.raw.clone(),
+				file.as_ptr(),
+				thumbnail.map_or(ptr::null(), CStr::as_ptr),
+				width as i32,
+				height as i32,
+			)
+		};
+		if handle == INVALID_SCREENSHOT {
+			None
+		} else {
+			Some(handle)
+		}
+	}
+
+	/// Takes a screenshot as if the user pressed the hotkey.
+	pub fn trigger_screenshot(&self) {
+		unsafe { SteamAPI_ISteamScreenshots_TriggerScreenshot(self.raw.clone()) }
+	}
+
+	/// Whether the game takes screenshots itself upon `ScreenshotRequested`, rather than Steam.
+	pub fn hook_screenshots(&self, hook: bool) {
+		unsafe { SteamAPI_ISteamScreenshots_HookScreenshots(self.raw.clone(), hook) }
+	}
+
+	pub fn is_screenshots_hooked(&self) -> bool {
+		unsafe { SteamAPI_ISteamScreenshots_IsScreenshotsHooked(self.raw.clone()) }
+	}
+
+	/// Sets where a screenshot was taken, e.g. the name of the map.
+	pub fn set_location(&self, screenshot: ScreenshotHandle, location: &CStr) -> Result<(), ()> {
+		if unsafe {
+			SteamAPI_ISteamScreenshots_SetLocation(self.raw.clone(), screenshot, location.as_ptr())
+		} {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	pub fn tag_user(&self, screenshot: ScreenshotHandle, user: SteamId) -> Result<(), ()> {
+		if unsafe { SteamAPI_ISteamScreenshots_TagUser(self.raw.clone(), screenshot, user) } {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	pub fn tag_published_file(&self, screenshot: ScreenshotHandle, item: Item) -> Result<(), ()> {
+		if unsafe {
+			SteamAPI_ISteamScreenshots_TagPublishedFile(self.raw.clone(), screenshot, item)
+		} {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	pub fn screenshots_ready(&self) -> impl Stream<Item = ScreenshotReady, Error = Error> + 'a {
+		self.callbacks.subscribe()
+	}
+
+	pub fn screenshot_requests(
+		&self,
+	) -> impl Stream<Item = ScreenshotRequested, Error = Error> + 'a {
+		self.callbacks.subscribe()
+	}
+}
+
+steam_extern! {
+	fn SteamAPI_ISteamClient_GetISteamScreenshots<'a>(a: Raw<Client<'a>>, b: User<'a>, c: Pipe<'a>, d: *const c_char) -> MaybeRaw<Screenshots<'a>>;
+
+	fn SteamAPI_ISteamScreenshots_WriteScreenshot(a: Raw<Screenshots<'_>>, b: *const u8, c: u32, d: i32, e: i32)                             -> ScreenshotHandle;
+	fn SteamAPI_ISteamScreenshots_AddScreenshotToLibrary(a: Raw<Screenshots<'_>>, b: *const c_char, c: *const c_char, d: i32, e: i32) -> ScreenshotHandle;
+	fn SteamAPI_ISteamScreenshots_TriggerScreenshot(a: Raw<Screenshots<'_>>);
+	fn SteamAPI_ISteamScreenshots_HookScreenshots(a: Raw<Screenshots<'_>>, b: bool);
+	fn SteamAPI_ISteamScreenshots_IsScreenshotsHooked(a: Raw<Screenshots<'_>>) -> bool;
+
+	fn SteamAPI_ISteamScreenshots_SetLocation(a: Raw<Screenshots<'_>>, b: ScreenshotHandle, c: *const c_char) -> bool;
+	fn SteamAPI_ISteamScreenshots_TagUser(a: Raw<Screenshots<'_>>, b: ScreenshotHandle, c: SteamId)           -> bool;
+	fn SteamAPI_ISteamScreenshots_TagPublishedFile(a: Raw<Screenshots<'_>>, b: ScreenshotHandle, c: Item)     -> bool;
+}
@@ -0,0 +1,218 @@
+use std::{ffi::CStr, fmt};
+
+use crate::UserStats;
+
+/// A set of stats and achievements declared with `declare_stats!`.
+pub trait StatsSchema: Sized {
+	const ACHIEVEMENTS: &'static [&'static str];
+	const STATS: &'static [&'static str];
+
+	fn load(stats: &UserStats<'_>) -> Result<Self, SchemaMismatch>;
+
+	/// Sets the stats and unlocks the achievements that are set, to be uploaded with `UserStats::store_stats`.
+	fn store(&self, stats: &UserStats<'_>) -> Result<(), SchemaMismatch>;
+}
+
+/// The differences between a `StatsSchema` and the app's schema on Steam.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SchemaMismatch {
+	/// Declared stats the app lacks, or has with another type.
+	pub stats:                   Vec<&'static str>,
+	/// Declared achievements the app lacks.
+	pub achievements:            Vec<&'static str>,
+	/// Achievements of the app that were not declared.
+	pub undeclared_achievements: Vec<String>,
+}
+
+impl SchemaMismatch {
+	pub fn is_empty(&self) -> bool {
+		self.stats.is_empty()
+			&& self.achievements.is_empty()
+			&& self.undeclared_achievements.is_empty()
+	}
+
+	#[doc(hidden)]
+	pub fn check_stat<T: Default>(&mut self, name: &'static str, value: Option<T>) -> T {
+		value.unwrap_or_else(|| {
+			self.stats.push(name);
+			T::default()
+		})
+	}
+
+	#[doc(hidden)]
+	pub fn check_achievement(&mut self, name: &'static str, value: Option<bool>) -> bool {
+		value.unwrap_or_else(|| {
+			self.achievements.push(name);
+			false
+		})
+	}
+
+	#[doc(hidden)]
+	pub fn into_result<T>(self, value: T) -> Result<T, Self> {
+		if self.is_empty() {
+			Ok(value)
+		} else {
+			Err(self)
+		}
+	}
+}
+
+impl fmt::Display for SchemaMismatch {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "Stats schema does not match the app's")?;
+		if !self.stats.is_empty() {
+			write!(f, "; missing or mistyped stats: {}", self.stats.join(", "))?;
+		}
+		if !self.achievements.is_empty() {
+			write!(
+				f,
+				"; missing achievements: {}",
+				self.achievements.join(", ")
+			)?;
+		}
+		if !self.undeclared_achievements.is_empty() {
+			write!(
+				f,
+				"; undeclared achievements: {}",
+				self.undeclared_achievements.join(", ")
+			)?;
+		}
+		Ok(())
+	}
+}
+
+impl std::error::Error for SchemaMismatch {}
+
+#[doc(hidden)]
+pub fn schema_name(name: &'static str) -> &'static CStr {
+	CStr::from_bytes_with_nul(name.as_bytes()).expect("stat names must not contain NUL")
+}
+
+impl UserStats<'_> {
+	pub fn load_schema<S: StatsSchema>(&self) -> Result<S, SchemaMismatch> {
+		S::load(self)
+	}
+
+	pub fn store_schema<S: StatsSchema>(&self, schema: &S) -> Result<(), SchemaMismatch> {
+		schema.store(self)
+	}
+
+	/// Checks `S` against the app's schema, which requires the current stats to have been received.
+	pub fn validate_schema<S: StatsSchema>(&self) -> Result<(), SchemaMismatch> {
+		let mut mismatch = match S::load(self) {
+			Ok(_) => SchemaMismatch::default(),
+			Err(mismatch) => mismatch,
+		};
+		mismatch.undeclared_achievements = (0..self.num_achievements())
+			.filter_map(|i| self.achievement_name(i))
+			.filter(|name| !S::ACHIEVEMENTS.contains(&name.as_str()))
+			.collect();
+		mismatch.into_result(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	crate::declare_stats! {
+		struct TestStats {
+			stats {
+				kills: i32 = "KILLS",
+				distance: f32 = "DISTANCE_TRAVELED",
+			}
+			achievements {
+				first_blood = "ACH_FIRST_BLOOD",
+				marathon = "ACH_MARATHON",
+			}
+		}
+	}
+
+	#[test]
+	fn declares_names_in_order() {
+		assert_eq!(TestStats::STATS, ["KILLS", "DISTANCE_TRAVELED"]);
+		assert_eq!(TestStats::ACHIEVEMENTS, ["ACH_FIRST_BLOOD", "ACH_MARATHON"]);
+	}
+
+	#[test]
+	fn declares_fields() {
+		let stats = TestStats {
+			kills:       3,
+			distance:    1.5,
+			first_blood: true,
+			marathon:    false,
+		};
+		assert_eq!(stats.clone(), stats);
+		assert_eq!(
+			TestStats::default(),
+			TestStats {
+				kills:       0,
+				distance:    0.0,
+				first_blood: false,
+				marathon:    false,
+			}
+		);
+	}
+
+	#[test]
+	fn names_are_nul_terminated() {
+		assert_eq!(schema_name("KILLS\0").to_bytes(), b"KILLS");
+	}
+
+	#[test]
+	fn collects_missing_names() {
+		let mut mismatch = SchemaMismatch::default();
+		assert_eq!(mismatch.check_stat("KILLS", Some(3)), 3);
+		assert_eq!(mismatch.check_stat::<f32>("DISTANCE_TRAVELED", None), 0.0);
+		assert!(mismatch.check_achievement("ACH_FIRST_BLOOD", Some(true)));
+		assert!(!mismatch.check_achievement("ACH_MARATHON", None));
+
+		assert_eq!(
+			mismatch,
+			SchemaMismatch {
+				stats:                   vec!["DISTANCE_TRAVELED"],
+				achievements:            vec!["ACH_MARATHON"],
+				undeclared_achievements: vec![],
+			}
+		);
+		assert_eq!(mismatch.clone().into_result(()), Err(mismatch));
+	}
+
+	#[test]
+	fn matches_when_nothing_is_missing() {
+		let mut mismatch = SchemaMismatch::default();
+		mismatch.check_stat("KILLS", Some(3));
+		mismatch.check_achievement("ACH_FIRST_BLOOD", Some(false));
+
+		assert!(mismatch.is_empty());
+		assert_eq!(
+			mismatch.into_result(TestStats::default()),
+			Ok(TestStats::default())
+		);
+	}
+
+	#[test]
+	fn displays_each_kind_of_mismatch() {
+		let mut mismatch = SchemaMismatch {
+			stats: vec!["KILLS", "DISTANCE_TRAVELED"],
+			..SchemaMismatch::default()
+		};
+		assert_eq!(
+			mismatch.to_string(),
+			"Stats schema does not match the app's; \
+			 missing or mistyped stats: KILLS, DISTANCE_TRAVELED"
+		);
+
+		mismatch.achievements.push("ACH_MARATHON");
+		mismatch
+			.undeclared_achievements
+			.push("ACH_SECRET".to_owned());
+		assert_eq!(
+			mismatch.to_string(),
+			"Stats schema does not match the app's; \
+			 missing or mistyped stats: KILLS, DISTANCE_TRAVELED; \
+			 missing achievements: ACH_MARATHON; \
+			 undeclared achievements: ACH_SECRET"
+		);
+	}
+}
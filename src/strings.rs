@@ -29,3 +29,12 @@ impl<'a, I: Iterator<Item = &'a CStr>> From<I> for StringsContainer<'a> {
 		}
 	}
 }
+
+/// Copies a string owned by Steam, which may be null.
+pub(crate) fn string_from_ptr(s: *const c_char) -> Option<String> {
+	if s.is_null() {
+		None
+	} else {
+		Some(unsafe { CStr::from_ptr(s) }.to_string_lossy().into_owned())
+	}
+}
@@ -0,0 +1,143 @@
+use const_cstr::const_cstr;
+use std::{ffi::CStr, os::raw::c_char, time::Duration};
+
+use crate::{Client, Raw};
+
+/// The highest priority a timeline event can have.
+pub const TIMELINE_PRIORITY_MAX: u32 = 1000;
+
+/// What the game is doing, shown as the color of the timeline.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TimelineGameMode {
+	Playing = 1,
+	/// Between rounds, e.g. in a lobby or on a loadout screen.
+	Staging,
+	Menus,
+	LoadingScreen,
+}
+
+/// Whether an event makes a good clip of the recording.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ClipPriority {
+	None = 1,
+	Standard,
+	Featured,
+}
+
+/// An event to mark on the timeline, added with `add`.
+pub struct TimelineEvent<'a, 's> {
+	timeline:     &'s Timeline<'a>,
+	icon:         &'s CStr,
+	title:        &'s CStr,
+	description:  &'s CStr,
+	priority:     u32,
+	start_offset: f32,
+	duration:     Duration,
+	clip:         ClipPriority,
+}
+
+impl<'a, 's> TimelineEvent<'a, 's> {
+	pub fn description(mut self, description: &'s CStr) -> Self {
+		self.description = description;
+		self
+	}
+
+	/// Which of overlapping events is shown, up to `TIMELINE_PRIORITY_MAX`.
+	pub fn priority(mut self, priority: u32) -> Self {
+		self.priority = priority.min(TIMELINE_PRIORITY_MAX);
+		self
+	}
+
+	/// When the event started, in seconds relative to now.
+	pub fn start_offset(mut self, seconds: f32) -> Self {
+		self.start_offset = seconds;
+		self
+	}
+
+	/// How long the event lasted, making it a range rather than a single point.
+	pub fn duration(mut self, duration: Duration) -> Self {
+		self.duration = duration;
+		self
+	}
+
+	pub fn clip(mut self, clip: ClipPriority) -> Self {
+		self.clip = clip;
+		self
+	}
+
+	pub fn add(self) {
+		unsafe {
+			SteamAPI_ISteamTimeline_AddTimelineEvent(
+				self.timeline.raw.clone(),
+				self.icon.as_ptr(),
+				self.title.as_ptr(),
+				self.description.as_ptr(),
+				self.priority,
+				self.start_offset,
+				self.duration.as_secs_f32(),
+				self.clip,
+			)
+		}
+	}
+}
+
+interface!(Timeline);
+impl<'a> Timeline<'a> {
+	pub fn new(client: &Client<'a>) -> Option<Self> {
+		let raw =
+			client.generic_interface(const_cstr!("STEAMTIMELINE_INTERFACE_V001").as_cstr())?;
+		let utils = client.utils.clone();
+		let callbacks = client.callbacks.clone();
+
+		Some(Timeline {
+			raw,
+			utils,
+			callbacks,
+		})
+	}
+
+	/// Describes what the game is doing from `time_delta` seconds relative to now, e.g. the map
+	/// being played.
+	pub fn set_state_description(&self, description: &CStr, time_delta: f32) {
+		unsafe {
+			SteamAPI_ISteamTimeline_SetTimelineStateDescription(
+				self.raw.clone(),
+				description.as_ptr(),
+				time_delta,
+			)
+		}
+	}
+
+	pub fn clear_state_description(&self, time_delta: f32) {
+		unsafe {
+			SteamAPI_ISteamTimeline_ClearTimelineStateDescription(self.raw.clone(), time_delta)
+		}
+	}
+
+	/// Starts an event happening now, `icon` naming one of Steam's icons or one of the app's.
+	pub fn event<'s>(&'s self, icon: &'s CStr, title: &'s CStr) -> TimelineEvent<'a, 's> {
+		TimelineEvent {
+			timeline: self,
+			icon,
+			title,
+			description: const_cstr!("").as_cstr(),
+			priority: 0,
+			start_offset: 0.0,
+			duration: Duration::from_secs(0),
+			clip: ClipPriority::None,
+		}
+	}
+
+	pub fn set_game_mode(&self, mode: TimelineGameMode) {
+		unsafe { SteamAPI_ISteamTimeline_SetTimelineGameMode(self.raw.clone(), mode) }
+	}
+}
+
+steam_extern! {
+	fn SteamAPI_ISteamTimeline_SetTimelineStateDescription(a: Raw<Timeline<'_>>, b: *const c_char, c: f32);
+	fn SteamAPI_ISteamTimeline_ClearTimelineStateDescription(a: Raw<Timeline<'_>>, b: f32);
+	fn SteamAPI_ISteamTimeline_AddTimelineEvent(a: Raw<Timeline<'_>>, b: *const c_char, c: *const c_char, d: *const c_char, e: u32, f: f32, g: f32, h: ClipPriority);
+	fn SteamAPI_ISteamTimeline_SetTimelineGameMode(a: Raw<Timeline<'_>>, b: TimelineGameMode);
+}
@@ -0,0 +1,217 @@
+use const_cstr::const_cstr;
+use derive_more::{Display, LowerHex, UpperHex};
+use std::{ffi::CStr, fmt, os::raw::c_char};
+
+use futures::{Async, Future, Poll, Stream};
+
+use crate::{APICall, Client, Error, MaybeAPICall, MaybeRaw, Pipe, Raw, RawResult, User};
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Hash, PartialOrd, Ord, UpperHex, LowerHex)]
+pub struct SteamId(pub u64);
+
+impl SteamId {
+	pub fn account_id(self) -> u32 {
+		self.0 as u32
+	}
+}
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AuthTicket(u32);
+
+/// A ticket to be verified by a backend through `ISteamUserAuth/AuthenticateUserTicket`.
+#[derive(Clone, Debug)]
+pub struct WebApiTicket {
+	pub handle: AuthTicket,
+	pub bytes:  Vec<u8>,
+}
+
+/// An encrypted app ticket, to be decrypted with the app's encryption key.
+#[derive(Clone, Debug)]
+pub struct EncryptedAppTicket {
+	pub bytes: Vec<u8>,
+}
+
+fn fmt_hex(bytes: &[u8], f: &mut fmt::Formatter<'_>, upper: bool) -> fmt::Result {
+	for b in bytes {
+		if upper {
+			write!(f, "{:02X}", b)?;
+		} else {
+			write!(f, "{:02x}", b)?;
+		}
+	}
+	Ok(())
+}
+
+macro_rules! ticket_hex {
+	($($ticket:ident),*) => {
+		$(
+			impl $ticket {
+				/// The ticket as the hex string the Web API expects.
+				pub fn to_hex(&self) -> String {
+					format!("{:X}", self)
+				}
+			}
+
+			impl fmt::UpperHex for $ticket {
+				fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+					fmt_hex(&self.bytes, f, true)
+				}
+			}
+
+			impl fmt::LowerHex for $ticket {
+				fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+					fmt_hex(&self.bytes, f, false)
+				}
+			}
+		)*
+	};
+}
+
+ticket_hex!(WebApiTicket, EncryptedAppTicket);
+
+struct WebApiTicketResponse {
+	handle: AuthTicket,
+	ticket: Result<Vec<u8>, Error>,
+}
+
+declare_notification! {
+	Data (168) {
+		handle:     AuthTicket,
+		result:     RawResult,
+		ticket_len: i32,
+		ticket:     [u8; 2560],
+	} -> WebApiTicketResponse;
+
+	map(
+		|Data {
+			handle,
+			result,
+			ticket_len,
+			ticket,
+		}| {
+			let len = (ticket_len as usize).min(ticket.len());
+			WebApiTicketResponse {
+				handle,
+				ticket: Result::from(result).map(|_| ticket[..len].to_vec()),
+			}
+		}
+	);
+}
+
+interface!(SteamUser);
+impl<'a> SteamUser<'a> {
+	pub fn new(client: &Client<'a>) -> Option<Self> {
+		let raw = unsafe {
+			SteamAPI_ISteamClient_GetISteamUser(
+				client.raw.clone(),
+				client.user(),
+				client.pipe(),
+				const_cstr!("SteamUser023").as_ptr(),
+			)
+			.check()?
+		};
+		let utils = client.utils.clone();
+		let callbacks = client.callbacks.clone();
+
+		Some(SteamUser {
+			raw,
+			utils,
+			callbacks,
+		})
+	}
+
+	pub fn steam_id(&self) -> SteamId {
+		unsafe { SteamAPI_ISteamUser_GetSteamID(self.raw.clone()) }
+	}
+
+	/// Requests a ticket for the Web API, `identity` naming the service that will verify it.
+	pub fn web_api_ticket(
+		&'a self,
+		identity: &CStr,
+	) -> Option<impl Future<Item = WebApiTicket, Error = Error> + 'a> {
+		let responses = self.callbacks.subscribe::<WebApiTicketResponse>();
+		let handle = unsafe {
+			SteamAPI_ISteamUser_GetAuthTicketForWebApi(self.raw.clone(), identity.as_ptr())
+		};
+		if handle.0 == 0 {
+			return None;
+		}
+
+		Some(
+			responses
+				.filter(move |response| response.handle == handle)
+				.into_future()
+				.map_err(|(e, _)| e)
+				.and_then(move |(response, _)| {
+					let response = response.ok_or(Error::Fail)?;
+					response.ticket.map(|bytes| WebApiTicket { handle, bytes })
+				}),
+		)
+	}
+
+	pub fn cancel_auth_ticket(&self, ticket: AuthTicket) {
+		unsafe { SteamAPI_ISteamUser_CancelAuthTicket(self.raw.clone(), ticket) }
+	}
+
+	/// Requests an encrypted app ticket, embedding `data` as its user variable data.
+	pub fn encrypted_app_ticket(
+		&'a self,
+		data: impl AsRef<[u8]>,
+	) -> Option<impl Future<Item = EncryptedAppTicket, Error = Error> + 'a> {
+		declare_future! {
+			Data (154) {
+				result: RawResult,
+			} -> ();
+
+			map(|Data {result}| Result::from(result));
+		}
+
+		let data = data.as_ref();
+		let api_call = unsafe {
+			SteamAPI_ISteamUser_RequestEncryptedAppTicket(
+				self.raw.clone(),
+				data.as_ptr(),
+				data.len() as i32,
+			)
+		};
+
+		let handle = Handle {
+			api_call: unsafe { APICall::new(api_call)? },
+			utils:    self.utils.clone(),
+		};
+		Some(handle.and_then(move |()| self.last_encrypted_app_ticket().ok_or(Error::Fail)))
+	}
+
+	/// The ticket retrieved by the last completed `encrypted_app_ticket` request.
+	pub fn last_encrypted_app_ticket(&self) -> Option<EncryptedAppTicket> {
+		let mut bytes = vec![0; 1024];
+		let mut len = 0u32;
+		if unsafe {
+			SteamAPI_ISteamUser_GetEncryptedAppTicket(
+				self.raw.clone(),
+				bytes.as_mut_ptr(),
+				bytes.len() as i32,
+				&mut len as *mut u32,
+			)
+		} {
+			bytes.truncate(len as usize);
+			Some(EncryptedAppTicket { bytes })
+		} else {
+			None
+		}
+	}
+}
+
+steam_extern! {
+	fn SteamAPI_ISteamClient_GetISteamUser<'a>(a: Raw<Client<'a>>, b: User<'a>, c: Pipe<'a>, d: *const c_char) -> MaybeRaw<SteamUser<'a>>;
+
+	fn SteamAPI_ISteamUser_GetSteamID(a: Raw<SteamUser<'_>>) -> SteamId;
+
+	fn SteamAPI_ISteamUser_GetAuthTicketForWebApi(a: Raw<SteamUser<'_>>, b: *const c_char) -> AuthTicket;
+	fn SteamAPI_ISteamUser_CancelAuthTicket(a: Raw<SteamUser<'_>>, b: AuthTicket);
+
+	fn SteamAPI_ISteamUser_RequestEncryptedAppTicket(a: Raw<SteamUser<'_>>, b: *const u8, c: i32)               -> MaybeAPICall;
+	fn SteamAPI_ISteamUser_GetEncryptedAppTicket(a: Raw<SteamUser<'_>>, b: *mut u8, c: i32, d: *mut u32) -> bool;
+}
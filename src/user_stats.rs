@@ -0,0 +1,822 @@
+use const_cstr::const_cstr;
+use std::{
+	ffi::CStr,
+	os::raw::c_char,
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use futures::{Async, Future, Poll, Stream};
+
+use crate::{
+	string_from_ptr,
+	APICall,
+	Client,
+	Error,
+	ImageHandle,
+	MaybeAPICall,
+	MaybeRaw,
+	Notifications,
+	Pipe,
+	Raw,
+	RawResult,
+	SteamId,
+	SteamUser,
+	Ugc,
+	User,
+};
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Leaderboard(pub u64);
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug)]
+struct LeaderboardEntries(u64);
+
+#[repr(u32)]
+pub enum LeaderboardSortMethod {
+	Ascending = 1,
+	Descending,
+}
+
+#[repr(u32)]
+pub enum LeaderboardDisplayType {
+	Numeric = 1,
+	TimeSeconds,
+	TimeMilliseconds,
+}
+
+#[repr(u32)]
+pub enum UploadScoreMethod {
+	KeepBest = 1,
+	ForceUpdate,
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy)]
+enum LeaderboardDataRequest {
+	Global,
+	GlobalAroundUser,
+	Friends,
+}
+
+/// Which entries of a leaderboard to download.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LeaderboardRange {
+	/// Entries by global rank, starting at 1, inclusive.
+	Global { start: i32, end: i32 },
+	/// Entries relative to the user's own, e.g. `-5` to `5`.
+	AroundUser { start: i32, end: i32 },
+	/// The entries of the user and their friends.
+	Friends,
+}
+
+/// The maximum number of details that can be attached to a leaderboard entry.
+pub const LEADERBOARD_DETAILS_MAX: usize = 64;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LeaderboardEntry {
+	pub user:    SteamId,
+	pub rank:    i32,
+	pub score:   i32,
+	pub details: Vec<i32>,
+	pub ugc:     Option<Ugc>,
+}
+
+#[repr(C, packed)]
+struct RawLeaderboardEntry {
+	user:        SteamId,
+	rank:        i32,
+	score:       i32,
+	details_len: i32,
+	ugc:         Ugc,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScoreUploaded {
+	pub leaderboard:          Leaderboard,
+	pub score:                i32,
+	/// Whether the score replaced the user's previous one.
+	pub changed:              bool,
+	pub global_rank:          i32,
+	pub previous_global_rank: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UserStatsReceived {
+	pub game_id: u64,
+	pub result:  Result<(), Error>,
+	pub user:    SteamId,
+}
+
+declare_notification! {
+	Data (1101) {
+		game_id: u64,
+		result:  RawResult,
+		user:    SteamId,
+	} -> UserStatsReceived;
+
+	map(|Data {game_id, result, user}| UserStatsReceived {
+		game_id,
+		result: Result::from(result),
+		user,
+	});
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UserStatsStored {
+	pub game_id: u64,
+	pub result:  Result<(), Error>,
+}
+
+declare_notification! {
+	Data (1102) {
+		game_id: u64,
+		result:  RawResult,
+	} -> UserStatsStored;
+
+	map(|Data {game_id, result}| UserStatsStored {
+		game_id,
+		result: Result::from(result),
+	});
+}
+
+/// How many days of history global stats can be requested for.
+pub const GLOBAL_STAT_HISTORY_DAYS_MAX: usize = 60;
+
+/// The types a global stat can be aggregated as.
+pub trait GlobalStatValue: Copy + Default {
+	#[doc(hidden)]
+	fn get(stats: &UserStats<'_>, name: &CStr) -> Option<Self>;
+
+	#[doc(hidden)]
+	fn history(stats: &UserStats<'_>, name: &CStr, history: &mut [Self]) -> Option<usize>;
+}
+
+macro_rules! global_stat_values {
+	($($ty:ty: $get:ident $history:ident);*;) => {
+		$(
+			impl GlobalStatValue for $ty {
+				fn get(stats: &UserStats<'_>, name: &CStr) -> Option<Self> {
+					let mut value = Default::default();
+					if unsafe { $get(stats.raw.clone(), name.as_ptr(), &mut value as *mut $ty) } {
+						Some(value)
+					} else {
+						None
+					}
+				}
+
+				fn history(stats: &UserStats<'_>, name: &CStr, history: &mut [Self]) -> Option<usize> {
+					let len = unsafe {
+						$history(
+							stats.raw.clone(),
+							name.as_ptr(),
+							history.as_mut_ptr(),
+							std::mem::size_of_val(history) as u32,
+						)
+					};
+					if len > 0 {
+						Some(len as usize)
+					} else {
+						None
+					}
+				}
+			}
+		)*
+	};
+}
+
+global_stat_values!(
+	i64: SteamAPI_ISteamUserStats_GetGlobalStatInt64 SteamAPI_ISteamUserStats_GetGlobalStatHistoryInt64;
+	f64: SteamAPI_ISteamUserStats_GetGlobalStatDouble SteamAPI_ISteamUserStats_GetGlobalStatHistoryDouble;
+);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AchievementPercentage {
+	pub name:     String,
+	pub percent:  f32,
+	/// Whether the current user has unlocked the achievement.
+	pub achieved: bool,
+}
+
+/// The achievements of the app, from the most to the least achieved.
+pub struct MostAchieved<'b, 'a> {
+	stats: &'b UserStats<'a>,
+	next:  Option<i32>,
+}
+
+impl Iterator for MostAchieved<'_, '_> {
+	type Item = AchievementPercentage;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let mut name = [0 as c_char; 128];
+		let mut percent = 0.0;
+		let mut achieved = false;
+		let iter = unsafe {
+			match self.next {
+				None => SteamAPI_ISteamUserStats_GetMostAchievedAchievementInfo(
+					self.stats.raw.clone(),
+					name.as_mut_ptr(),
+					name.len() as u32,
+					&mut percent as *mut f32,
+					&mut achieved as *mut bool,
+				),
+				Some(-1) => return None,
+				Some(prev) => SteamAPI_ISteamUserStats_GetNextMostAchievedAchievementInfo(
+					self.stats.raw.clone(),
+					prev,
+					name.as_mut_ptr(),
+					name.len() as u32,
+					&mut percent as *mut f32,
+					&mut achieved as *mut bool,
+				),
+			}
+		};
+		self.next = Some(iter);
+		if iter == -1 {
+			return None;
+		}
+
+		Some(AchievementPercentage {
+			name: cstr_to_string(name.as_ptr()).unwrap_or_default(),
+			percent,
+			achieved,
+		})
+	}
+}
+
+/// The types a stat can be stored as.
+pub trait StatValue: Copy {
+	#[doc(hidden)]
+	fn get(stats: &UserStats<'_>, name: &CStr) -> Option<Self>;
+
+	#[doc(hidden)]
+	fn set(stats: &UserStats<'_>, name: &CStr, value: Self) -> Result<(), ()>;
+}
+
+macro_rules! stat_values {
+	($($ty:ty: $get:ident $set:ident);*;) => {
+		$(
+			impl StatValue for $ty {
+				fn get(stats: &UserStats<'_>, name: &CStr) -> Option<Self> {
+					let mut value = Default::default();
+					if unsafe { $get(stats.raw.clone(), name.as_ptr(), &mut value as *mut $ty) } {
+						Some(value)
+					} else {
+						None
+					}
+				}
+
+				fn set(stats: &UserStats<'_>, name: &CStr, value: Self) -> Result<(), ()> {
+					if unsafe { $set(stats.raw.clone(), name.as_ptr(), value) } {
+						Ok(())
+					} else {
+						Err(())
+					}
+				}
+			}
+		)*
+	};
+}
+
+stat_values!(
+	i32: SteamAPI_ISteamUserStats_GetStatInt32 SteamAPI_ISteamUserStats_SetStatInt32;
+	f32: SteamAPI_ISteamUserStats_GetStatFloat SteamAPI_ISteamUserStats_SetStatFloat;
+);
+
+fn cstr_to_string(s: *const c_char) -> Option<String> {
+	string_from_ptr(s).filter(|s| !s.is_empty())
+}
+
+interface!(UserStats);
+impl<'a> UserStats<'a> {
+	pub fn new(client: &Client<'a>) -> Option<Self> {
+		let raw = unsafe {
+			SteamAPI_ISteamClient_GetISteamUserStats(
+				client.raw.clone(),
+				client.user(),
+				client.pipe(),
+				const_cstr!("STEAMUSERSTATS_INTERFACE_VERSION012").as_ptr(),
+			)
+			.check()?
+		};
+		let utils = client.utils.clone();
+		let callbacks = client.callbacks.clone();
+
+		Some(UserStats {
+			raw,
+			utils,
+			callbacks,
+		})
+	}
+
+	pub fn stats_received(&self) -> Notifications<'a, UserStatsReceived> {
+		self.callbacks.subscribe()
+	}
+
+	pub fn stats_stored(&self) -> Notifications<'a, UserStatsStored> {
+		self.callbacks.subscribe()
+	}
+
+	/// Requests the current user's stats and achievements,
+	/// which must be received before any are read or written.
+	///
+	/// `user` identifies the local user, since stats received for other users
+	/// or games are delivered through the same notification.
+	pub fn request_current_stats(
+		&self,
+		user: &SteamUser<'_>,
+	) -> Option<impl Future<Item = UserStatsReceived, Error = Error> + 'a> {
+		let user = user.steam_id();
+		let game_id = u64::from(self.utils.app_id());
+		let received = self.stats_received();
+		if unsafe { !SteamAPI_ISteamUserStats_RequestCurrentStats(self.raw.clone()) } {
+			return None;
+		}
+
+		Some(
+			received
+				.filter(move |received| received.user == user && received.game_id == game_id)
+				.into_future()
+				.map_err(|(e, _)| e)
+				.and_then(|(received, _)| {
+					let received = received.ok_or(Error::Fail)?;
+					received.result.map(|()| received)
+				}),
+		)
+	}
+
+	/// Uploads the changed stats and achievements.
+	pub fn store_stats(&self) -> Option<impl Future<Item = (), Error = Error> + 'a> {
+		let stored = self.stats_stored();
+		if unsafe { !SteamAPI_ISteamUserStats_StoreStats(self.raw.clone()) } {
+			return None;
+		}
+
+		Some(
+			stored
+				.into_future()
+				.map_err(|(e, _)| e)
+				.and_then(|(stored, _)| stored.ok_or(Error::Fail)?.result),
+		)
+	}
+
+	pub fn stat<T: StatValue>(&self, name: &CStr) -> Option<T> {
+		T::get(self, name)
+	}
+
+	pub fn set_stat<T: StatValue>(&self, name: &CStr, value: T) -> Result<(), ()> {
+		T::set(self, name, value)
+	}
+
+	pub fn achievement(&self, name: &CStr) -> Option<bool> {
+		let mut achieved = false;
+		if unsafe {
+			SteamAPI_ISteamUserStats_GetAchievement(
+				self.raw.clone(),
+				name.as_ptr(),
+				&mut achieved as *mut bool,
+			)
+		} {
+			Some(achieved)
+		} else {
+			None
+		}
+	}
+
+	/// When the achievement was unlocked, if it has been.
+	pub fn achievement_unlock_time(&self, name: &CStr) -> Option<SystemTime> {
+		let mut achieved = false;
+		let mut unlock_time = 0u32;
+		if unsafe {
+			SteamAPI_ISteamUserStats_GetAchievementAndUnlockTime(
+				self.raw.clone(),
+				name.as_ptr(),
+				&mut achieved as *mut bool,
+				&mut unlock_time as *mut u32,
+			)
+		} && achieved
+		{
+			Some(UNIX_EPOCH + Duration::from_secs(unlock_time.into()))
+		} else {
+			None
+		}
+	}
+
+	pub fn set_achievement(&self, name: &CStr) -> Result<(), ()> {
+		if unsafe { SteamAPI_ISteamUserStats_SetAchievement(self.raw.clone(), name.as_ptr()) } {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	pub fn clear_achievement(&self, name: &CStr) -> Result<(), ()> {
+		if unsafe { SteamAPI_ISteamUserStats_ClearAchievement(self.raw.clone(), name.as_ptr()) } {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	/// Reads the `name`, `desc` or `hidden` display attribute of an achievement.
+	pub fn achievement_display_attribute(&self, name: &CStr, key: &CStr) -> Option<String> {
+		cstr_to_string(unsafe {
+			SteamAPI_ISteamUserStats_GetAchievementDisplayAttribute(
+				self.raw.clone(),
+				name.as_ptr(),
+				key.as_ptr(),
+			)
+		})
+	}
+
+	/// The icon of the achievement in its current state, or `None` if it has not been fetched yet.
+	pub fn achievement_icon(&self, name: &CStr) -> Option<ImageHandle> {
+		match unsafe {
+			SteamAPI_ISteamUserStats_GetAchievementIcon(self.raw.clone(), name.as_ptr())
+		} {
+			ImageHandle(0) => None,
+			icon => Some(icon),
+		}
+	}
+
+	pub fn num_achievements(&self) -> u32 {
+		unsafe { SteamAPI_ISteamUserStats_GetNumAchievements(self.raw.clone()) }
+	}
+
+	/// The API name of the achievement at `index`, for `0..num_achievements()`.
+	pub fn achievement_name(&self, index: u32) -> Option<String> {
+		cstr_to_string(unsafe {
+			SteamAPI_ISteamUserStats_GetAchievementName(self.raw.clone(), index)
+		})
+	}
+
+	/// Requests the global stats, with up to `GLOBAL_STAT_HISTORY_DAYS_MAX` days of history.
+	pub fn request_global_stats(
+		&'a self,
+		history_days: u32,
+	) -> Option<impl Future<Item = (), Error = Error> + 'a> {
+		declare_future! {
+			Data (1112) {
+				_game_id: u64,
+				result:   RawResult,
+			} -> ();
+
+			map(|Data {result, ..}| Result::from(result));
+		}
+
+		let history_days = history_days.min(GLOBAL_STAT_HISTORY_DAYS_MAX as u32);
+		let api_call = unsafe {
+			SteamAPI_ISteamUserStats_RequestGlobalStats(self.raw.clone(), history_days as i32)
+		};
+
+		Some(Handle {
+			api_call: unsafe { APICall::new(api_call)? },
+			utils:    self.utils.clone(),
+		})
+	}
+
+	pub fn global_stat<T: GlobalStatValue>(&self, name: &CStr) -> Option<T> {
+		T::get(self, name)
+	}
+
+	/// The daily history of a global stat, starting with today.
+	pub fn global_stat_history<T: GlobalStatValue>(&self, name: &CStr) -> Option<Vec<T>> {
+		let mut history = vec![T::default(); GLOBAL_STAT_HISTORY_DAYS_MAX];
+		let len = T::history(self, name, &mut history)?;
+		history.truncate(len);
+		Some(history)
+	}
+
+	pub fn request_global_achievement_percentages(
+		&'a self,
+	) -> Option<impl Future<Item = (), Error = Error> + 'a> {
+		declare_future! {
+			Data (1110) {
+				_game_id: u64,
+				result:   RawResult,
+			} -> ();
+
+			map(|Data {result, ..}| Result::from(result));
+		}
+
+		let api_call = unsafe {
+			SteamAPI_ISteamUserStats_RequestGlobalAchievementPercentages(self.raw.clone())
+		};
+
+		Some(Handle {
+			api_call: unsafe { APICall::new(api_call)? },
+			utils:    self.utils.clone(),
+		})
+	}
+
+	pub fn achievement_achieved_percent(&self, name: &CStr) -> Option<f32> {
+		let mut percent = 0.0;
+		if unsafe {
+			SteamAPI_ISteamUserStats_GetAchievementAchievedPercent(
+				self.raw.clone(),
+				name.as_ptr(),
+				&mut percent as *mut f32,
+			)
+		} {
+			Some(percent)
+		} else {
+			None
+		}
+	}
+
+	/// Requires the global achievement percentages to have been requested.
+	pub fn most_achieved_achievements(&self) -> MostAchieved<'_, 'a> {
+		MostAchieved {
+			stats: self,
+			next:  None,
+		}
+	}
+
+	pub fn find_leaderboard(
+		&'a self,
+		name: &CStr,
+	) -> Option<impl Future<Item = Leaderboard, Error = Error> + 'a> {
+		let api_call =
+			unsafe { SteamAPI_ISteamUserStats_FindLeaderboard(self.raw.clone(), name.as_ptr()) };
+
+		self.leaderboard_found(api_call)
+	}
+
+	pub fn find_or_create_leaderboard(
+		&'a self,
+		name: &CStr,
+		sort_method: LeaderboardSortMethod,
+		display_type: LeaderboardDisplayType,
+	) -> Option<impl Future<Item = Leaderboard, Error = Error> + 'a> {
+		let api_call = unsafe {
+			SteamAPI_ISteamUserStats_FindOrCreateLeaderboard(
+				self.raw.clone(),
+				name.as_ptr(),
+				sort_method,
+				display_type,
+			)
+		};
+
+		self.leaderboard_found(api_call)
+	}
+
+	fn leaderboard_found(
+		&'a self,
+		api_call: MaybeAPICall,
+	) -> Option<impl Future<Item = Leaderboard, Error = Error> + 'a> {
+		declare_future! {
+			Data (1104) {
+				leaderboard: Leaderboard,
+				found:       u8,
+			} -> Leaderboard;
+
+			map(|Data {leaderboard, found}| {
+				if found != 0 {
+					Ok(leaderboard)
+				} else {
+					Err(Error::FileNotFound)
+				}
+			});
+		}
+
+		Some(Handle {
+			api_call: unsafe { APICall::new(api_call)? },
+			utils:    self.utils.clone(),
+		})
+	}
+
+	/// Uploads a score with up to `LEADERBOARD_DETAILS_MAX` details of game-specific data.
+	pub fn upload_leaderboard_score(
+		&'a self,
+		leaderboard: Leaderboard,
+		method: UploadScoreMethod,
+		score: i32,
+		details: &[i32],
+	) -> Option<impl Future<Item = ScoreUploaded, Error = Error> + 'a> {
+		declare_future! {
+			Data (1106) {
+				success:              u8,
+				_pad0:                [u8; 3],
+				leaderboard:          Leaderboard,
+				score:                i32,
+				changed:              u8,
+				_pad1:                [u8; 3],
+				global_rank:          i32,
+				previous_global_rank: i32,
+			} -> ScoreUploaded;
+
+			map(
+				|Data {
+					success,
+					leaderboard,
+					score,
+					changed,
+					global_rank,
+					previous_global_rank,
+					..
+				}| {
+					if success != 0 {
+						Ok(ScoreUploaded {
+							leaderboard,
+							score,
+							changed: changed != 0,
+							global_rank,
+							previous_global_rank,
+						})
+					} else {
+						Err(Error::Fail)
+					}
+				}
+			);
+		}
+
+		if details.len() > LEADERBOARD_DETAILS_MAX {
+			return None;
+		}
+
+		let api_call = unsafe {
+			SteamAPI_ISteamUserStats_UploadLeaderboardScore(
+				self.raw.clone(),
+				leaderboard,
+				method,
+				score,
+				details.as_ptr(),
+				details.len() as i32,
+			)
+		};
+
+		Some(Handle {
+			api_call: unsafe { APICall::new(api_call)? },
+			utils:    self.utils.clone(),
+		})
+	}
+
+	pub fn download_leaderboard_entries(
+		&'a self,
+		leaderboard: Leaderboard,
+		range: LeaderboardRange,
+	) -> Option<impl Future<Item = Vec<LeaderboardEntry>, Error = Error> + 'a> {
+		let (request, start, end) = match range {
+			LeaderboardRange::Global { start, end } => (LeaderboardDataRequest::Global, start, end),
+			LeaderboardRange::AroundUser { start, end } => {
+				(LeaderboardDataRequest::GlobalAroundUser, start, end)
+			},
+			LeaderboardRange::Friends => (LeaderboardDataRequest::Friends, 0, 0),
+		};
+
+		let api_call = unsafe {
+			SteamAPI_ISteamUserStats_DownloadLeaderboardEntries(
+				self.raw.clone(),
+				leaderboard,
+				request,
+				start,
+				end,
+			)
+		};
+
+		self.entries_downloaded(api_call)
+	}
+
+	pub fn download_leaderboard_entries_for_users(
+		&'a self,
+		leaderboard: Leaderboard,
+		users: &[SteamId],
+	) -> Option<impl Future<Item = Vec<LeaderboardEntry>, Error = Error> + 'a> {
+		let api_call = unsafe {
+			SteamAPI_ISteamUserStats_DownloadLeaderboardEntriesForUsers(
+				self.raw.clone(),
+				leaderboard,
+				users.as_ptr(),
+				users.len() as i32,
+			)
+		};
+
+		self.entries_downloaded(api_call)
+	}
+
+	fn entries_downloaded(
+		&'a self,
+		api_call: MaybeAPICall,
+	) -> Option<impl Future<Item = Vec<LeaderboardEntry>, Error = Error> + 'a> {
+		declare_future! {
+			Data (1105) {
+				_leaderboard: Leaderboard,
+				entries:      LeaderboardEntries,
+				count:        i32,
+			} -> (LeaderboardEntries, i32);
+
+			map(|Data {entries, count, ..}| Ok((entries, count)));
+		}
+
+		let handle = Handle {
+			api_call: unsafe { APICall::new(api_call)? },
+			utils:    self.utils.clone(),
+		};
+		Some(handle.map(move |(entries, count)| {
+			(0..count)
+				.filter_map(|i| self.downloaded_entry(entries, i))
+				.collect()
+		}))
+	}
+
+	fn downloaded_entry(
+		&self,
+		entries: LeaderboardEntries,
+		index: i32,
+	) -> Option<LeaderboardEntry> {
+		let mut entry: RawLeaderboardEntry = unsafe { std::mem::zeroed() };
+		let mut details = vec![0; LEADERBOARD_DETAILS_MAX];
+		if unsafe {
+			!SteamAPI_ISteamUserStats_GetDownloadedLeaderboardEntry(
+				self.raw.clone(),
+				entries,
+				index,
+				&mut entry as *mut RawLeaderboardEntry,
+				details.as_mut_ptr(),
+				details.len() as i32,
+			)
+		} {
+			return None;
+		}
+
+		let RawLeaderboardEntry {
+			user,
+			rank,
+			score,
+			details_len,
+			ugc,
+		} = entry;
+		details.truncate((details_len as usize).min(LEADERBOARD_DETAILS_MAX));
+
+		Some(LeaderboardEntry {
+			user,
+			rank,
+			score,
+			details,
+			ugc: if ugc.0 == u64::MAX { None } else { Some(ugc) },
+		})
+	}
+}
+
+steam_extern! {
+	fn SteamAPI_ISteamClient_GetISteamUserStats<'a>(a: Raw<Client<'a>>, b: User<'a>, c: Pipe<'a>, d: *const c_char) -> MaybeRaw<UserStats<'a>>;
+
+	fn SteamAPI_ISteamUserStats_RequestCurrentStats(a: Raw<UserStats<'_>>) -> bool;
+	fn SteamAPI_ISteamUserStats_StoreStats(a: Raw<UserStats<'_>>)          -> bool;
+
+	fn SteamAPI_ISteamUserStats_GetStatInt32(a: Raw<UserStats<'_>>, b: *const c_char, c: *mut i32) -> bool;
+	fn SteamAPI_ISteamUserStats_GetStatFloat(a: Raw<UserStats<'_>>, b: *const c_char, c: *mut f32) -> bool;
+	fn SteamAPI_ISteamUserStats_SetStatInt32(a: Raw<UserStats<'_>>, b: *const c_char, c: i32)      -> bool;
+	fn SteamAPI_ISteamUserStats_SetStatFloat(a: Raw<UserStats<'_>>, b: *const c_char, c: f32)      -> bool;
+
+	fn SteamAPI_ISteamUserStats_GetAchievement(a: Raw<UserStats<'_>>, b: *const c_char, c: *mut bool)                            -> bool;
+	fn SteamAPI_ISteamUserStats_GetAchievementAndUnlockTime(a: Raw<UserStats<'_>>, b: *const c_char, c: *mut bool, d: *mut u32) -> bool;
+	fn SteamAPI_ISteamUserStats_SetAchievement(a: Raw<UserStats<'_>>, b: *const c_char)                                          -> bool;
+	fn SteamAPI_ISteamUserStats_ClearAchievement(a: Raw<UserStats<'_>>, b: *const c_char)                                        -> bool;
+
+	fn SteamAPI_ISteamUserStats_GetAchievementDisplayAttribute(a: Raw<UserStats<'_>>, b: *const c_char, c: *const c_char) -> *const c_char;
+	fn SteamAPI_ISteamUserStats_GetAchievementIcon(a: Raw<UserStats<'_>>, b: *const c_char)                               -> ImageHandle;
+	fn SteamAPI_ISteamUserStats_GetNumAchievements(a: Raw<UserStats<'_>>)                                                 -> u32;
+	fn SteamAPI_ISteamUserStats_GetAchievementName(a: Raw<UserStats<'_>>, b: u32)                                         -> *const c_char;
+
+	fn SteamAPI_ISteamUserStats_RequestGlobalStats(a: Raw<UserStats<'_>>, b: i32) -> MaybeAPICall;
+
+	fn SteamAPI_ISteamUserStats_GetGlobalStatInt64(a: Raw<UserStats<'_>>, b: *const c_char, c: *mut i64)                 -> bool;
+	fn SteamAPI_ISteamUserStats_GetGlobalStatDouble(a: Raw<UserStats<'_>>, b: *const c_char, c: *mut f64)                -> bool;
+	fn SteamAPI_ISteamUserStats_GetGlobalStatHistoryInt64(a: Raw<UserStats<'_>>, b: *const c_char, c: *mut i64, d: u32)  -> i32;
+	fn SteamAPI_ISteamUserStats_GetGlobalStatHistoryDouble(a: Raw<UserStats<'_>>, b: *const c_char, c: *mut f64, d: u32) -> i32;
+
+	fn SteamAPI_ISteamUserStats_RequestGlobalAchievementPercentages(a: Raw<UserStats<'_>>) -> MaybeAPICall;
+	fn SteamAPI_ISteamUserStats_GetAchievementAchievedPercent(a: Raw<UserStats<'_>>, b: *const c_char, c: *mut f32) -> bool;
+	fn SteamAPI_ISteamUserStats_GetMostAchievedAchievementInfo(a: Raw<UserStats<'_>>, b: *mut c_char, c: u32, d: *mut f32, e: *mut bool)              -> i32;
+	fn SteamAPI_ISteamUserStats_GetNextMostAchievedAchievementInfo(a: Raw<UserStats<'_>>, b: i32, c: *mut c_char, d: u32, e: *mut f32, f: *mut bool) -> i32;
+
+	fn SteamAPI_ISteamUserStats_FindLeaderboard(a: Raw<UserStats<'_>>, b: *const c_char) -> MaybeAPICall;
+	fn SteamAPI_ISteamUserStats_FindOrCreateLeaderboard(
+		a: Raw<UserStats<'_>>,
+		b: *const c_char,
+		c: LeaderboardSortMethod,
+		d: LeaderboardDisplayType
+	) -> MaybeAPICall;
+
+	fn SteamAPI_ISteamUserStats_UploadLeaderboardScore(
+		a: Raw<UserStats<'_>>,
+		b: Leaderboard,
+		c: UploadScoreMethod,
+		d: i32,
+		e: *const i32,
+		f: i32
+	) -> MaybeAPICall;
+
+	fn SteamAPI_ISteamUserStats_DownloadLeaderboardEntries(a: Raw<UserStats<'_>>, b: Leaderboard, c: LeaderboardDataRequest, d: i32, e: i32) -> MaybeAPICall;
+	fn SteamAPI_ISteamUserStats_DownloadLeaderboardEntriesForUsers(a: Raw<UserStats<'_>>, b: Leaderboard, c: *const SteamId, d: i32)         -> MaybeAPICall;
+	fn SteamAPI_ISteamUserStats_GetDownloadedLeaderboardEntry(
+		a: Raw<UserStats<'_>>,
+		b: LeaderboardEntries,
+		c: i32,
+		d: *mut RawLeaderboardEntry,
+		e: *mut i32,
+		f: i32
+	) -> bool;
+}
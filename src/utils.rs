@@ -1,10 +1,18 @@
+use lazy_static::lazy_static;
 use std::{
+	ffi::CStr,
 	marker::PhantomData,
 	mem::{size_of, zeroed},
 	num::NonZeroU64,
+	os::raw::c_char,
+	panic::{self, AssertUnwindSafe},
+	sync::{Arc, Mutex, PoisonError},
+	time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
-use crate::{Client, Interface, Raw};
+use futures::{Future, Stream};
+
+use crate::{string_from_ptr, Callbacks, Client, Error, Interface, Raw};
 
 #[repr(transparent)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -20,14 +28,139 @@ impl APICall<'_> {
 	}
 }
 
+/// An image held by Steam, such as an avatar or an achievement icon.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ImageHandle(pub(crate) i32);
+
+/// An image decoded from an `ImageHandle`, as rows of RGBA pixels.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+	pub width:  u32,
+	pub height: u32,
+	pub rgba:   Vec<u8>,
+}
+
+/// Where the overlay shows its notifications.
+#[repr(u32)]
+pub enum NotificationPosition {
+	TopLeft,
+	TopRight,
+	BottomLeft,
+	BottomRight,
+}
+
+/// Why an API call failed, as told by `Utils::api_call_failure_reason`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum APICallFailure {
+	/// The Steam client stopped running.
+	SteamGone,
+	/// The connection to Steam's servers was lost.
+	NetworkFailure,
+	/// The call is unknown, or has already been consumed.
+	InvalidHandle,
+	/// The call was checked for the wrong type of result.
+	MismatchedCallback,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WarningSeverity {
+	Message,
+	Warning,
+}
+
+type WarningHook = Arc<dyn Fn(WarningSeverity, &str) + Send + Sync>;
+
+lazy_static! {
+	static ref WARNING_HOOK: Mutex<Option<WarningHook>> = Mutex::new(None);
+}
+
+extern "C" fn warning_message(severity: i32, message: *const c_char) {
+	let message = string_from_ptr(message).unwrap_or_default();
+	let severity = if severity == 0 {
+		WarningSeverity::Message
+	} else {
+		WarningSeverity::Warning
+	};
+
+	// Called without holding the lock, so the hook may replace itself.
+	let hook = WARNING_HOOK
+		.lock()
+		.unwrap_or_else(PoisonError::into_inner)
+		.clone();
+	if let Some(hook) = hook {
+		// Unwinding into Steam is undefined behavior, so a panicking hook only loses its message.
+		let _ = panic::catch_unwind(AssertUnwindSafe(|| hook(severity, &message)));
+	}
+}
+
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GamepadTextInputMode {
+	Normal,
+	Password,
+}
+
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GamepadTextInputLineMode {
+	SingleLine,
+	MultipleLines,
+}
+
+/// Which keyboard layout the floating keyboard shows.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FloatingGamepadTextInputMode {
+	SingleLine,
+	MultipleLines,
+	Email,
+	Numeric,
+}
+
+/// The user closed the gamepad text input, submitting the text unless they cancelled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct GamepadTextInputDismissed {
+	pub submitted:     bool,
+	/// The length of the submitted text in bytes, not including the terminating NUL.
+	pub submitted_len: u32,
+	pub app_id:        u32,
+}
+
+declare_notification! {
+	Data (714) {
+		submitted:     bool,
+		_pad:          [u8; 3],
+		submitted_len: u32,
+		app_id:        u32,
+	} -> GamepadTextInputDismissed;
+
+	map(|Data {submitted, submitted_len, app_id, ..}| GamepadTextInputDismissed {
+		submitted,
+		submitted_len,
+		app_id,
+	});
+}
+
+/// The floating keyboard was closed; its text is typed into the focused field, not reported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FloatingGamepadTextInputDismissed;
+
+declare_notification! {
+	Data (738) {} -> FloatingGamepadTextInputDismissed;
+
+	map(|Data {}| FloatingGamepadTextInputDismissed);
+}
+
 pub unsafe trait APICallResult {
 	const ID: u32;
 }
 
 #[derive(Clone)]
 pub struct Utils<'a> {
-	pub(crate) raw:     Raw<Utils<'a>>,
-	pub(crate) _marker: PhantomData<&'a ()>,
+	pub(crate) raw:       Raw<Utils<'a>>,
+	pub(crate) callbacks: Callbacks<'a>,
+	pub(crate) _marker:   PhantomData<&'a ()>,
 }
 
 impl Interface for Utils<'_> {}
@@ -61,9 +194,252 @@ impl<'a> Utils<'a> {
 
 		Ok(result)
 	}
+
+	/// Why a call failed, or `None` if it hasn't.
+	pub fn api_call_failure_reason(&self, call: APICall<'_>) -> Option<APICallFailure> {
+		match unsafe { SteamAPI_ISteamUtils_GetAPICallFailureReason(self.raw.clone(), call) } {
+			0 => Some(APICallFailure::SteamGone),
+			1 => Some(APICallFailure::NetworkFailure),
+			2 => Some(APICallFailure::InvalidHandle),
+			3 => Some(APICallFailure::MismatchedCallback),
+			_ => None,
+		}
+	}
+
+	pub fn app_id(&self) -> u32 {
+		unsafe { SteamAPI_ISteamUtils_GetAppID(self.raw.clone()) }
+	}
+
+	/// The time according to Steam's servers, which the user can't tamper with.
+	pub fn server_real_time(&self) -> SystemTime {
+		let secs = unsafe { SteamAPI_ISteamUtils_GetServerRealTime(self.raw.clone()) };
+		UNIX_EPOCH + Duration::from_secs(secs.into())
+	}
+
+	pub fn since_app_active(&self) -> Duration {
+		let secs = unsafe { SteamAPI_ISteamUtils_GetSecondsSinceAppActive(self.raw.clone()) };
+		Duration::from_secs(secs.into())
+	}
+
+	pub fn since_computer_active(&self) -> Duration {
+		let secs = unsafe { SteamAPI_ISteamUtils_GetSecondsSinceComputerActive(self.raw.clone()) };
+		Duration::from_secs(secs.into())
+	}
+
+	/// The two-letter ISO 3166-1 code of the country the user's IP address is in.
+	pub fn ip_country(&self) -> String {
+		string_from_ptr(unsafe { SteamAPI_ISteamUtils_GetIPCountry(self.raw.clone()) })
+			.unwrap_or_default()
+	}
+
+	/// The battery level in percent, or `None` when running on AC power.
+	pub fn battery_power(&self) -> Option<u8> {
+		match unsafe { SteamAPI_ISteamUtils_GetCurrentBatteryPower(self.raw.clone()) } {
+			255 => None,
+			power => Some(power),
+		}
+	}
+
+	pub fn is_steam_running_on_steam_deck(&self) -> bool {
+		unsafe { SteamAPI_ISteamUtils_IsSteamRunningOnSteamDeck(self.raw.clone()) }
+	}
+
+	pub fn is_steam_in_big_picture_mode(&self) -> bool {
+		unsafe { SteamAPI_ISteamUtils_IsSteamInBigPictureMode(self.raw.clone()) }
+	}
+
+	/// Receives the messages and warnings Steam prints when the game is launched with
+	/// `-debug_steamapi`, replacing any previous hook.
+	pub fn set_warning_message_hook(
+		&self,
+		hook: impl Fn(WarningSeverity, &str) + Send + Sync + 'static,
+	) {
+		*WARNING_HOOK.lock().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(hook));
+		unsafe { SteamAPI_ISteamUtils_SetWarningMessageHook(self.raw.clone(), warning_message) }
+	}
+
+	/// Shows the Big Picture keyboard, resolving to the submitted text or `None` if the user
+	/// cancelled.
+	pub fn show_gamepad_text_input(
+		&self,
+		mode: GamepadTextInputMode,
+		line_mode: GamepadTextInputLineMode,
+		description: &CStr,
+		max_chars: u32,
+		existing_text: &CStr,
+	) -> Option<impl Future<Item = Option<String>, Error = Error> + 'a> {
+		let dismissals = self.gamepad_text_input_dismissals();
+		if unsafe {
+			!SteamAPI_ISteamUtils_ShowGamepadTextInput(
+				self.raw.clone(),
+				mode,
+				line_mode,
+				description.as_ptr(),
+				max_chars,
+				existing_text.as_ptr(),
+			)
+		} {
+			return None;
+		}
+
+		let utils = self.clone();
+		Some(
+			dismissals
+				.into_future()
+				.map_err(|(e, _)| e)
+				.and_then(move |(dismissed, _)| {
+					if dismissed.ok_or(Error::Fail)?.submitted {
+						utils.entered_gamepad_text().ok_or(Error::Fail).map(Some)
+					} else {
+						Ok(None)
+					}
+				}),
+		)
+	}
+
+	/// The text submitted through the last gamepad text input.
+	pub fn entered_gamepad_text(&self) -> Option<String> {
+		let len = unsafe { SteamAPI_ISteamUtils_GetEnteredGamepadTextLength(self.raw.clone()) };
+		let mut text = vec![0 as c_char; len as usize + 1];
+		if unsafe {
+			SteamAPI_ISteamUtils_GetEnteredGamepadTextInput(
+				self.raw.clone(),
+				text.as_mut_ptr(),
+				text.len() as u32,
+			)
+		} {
+			string_from_ptr(text.as_ptr())
+		} else {
+			None
+		}
+	}
+
+	pub fn gamepad_text_input_dismissals(
+		&self,
+	) -> impl Stream<Item = GamepadTextInputDismissed, Error = Error> + 'a {
+		self.callbacks.subscribe()
+	}
+
+	/// Shows the on-screen keyboard over the game, avoiding the given text field, and types
+	/// into whatever has focus.
+	pub fn show_floating_gamepad_text_input(
+		&self,
+		mode: FloatingGamepadTextInputMode,
+		x: i32,
+		y: i32,
+		width: i32,
+		height: i32,
+	) -> Result<(), ()> {
+		if unsafe {
+			SteamAPI_ISteamUtils_ShowFloatingGamepadTextInput(
+				self.raw.clone(),
+				mode,
+				x,
+				y,
+				width,
+				height,
+			)
+		} {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	pub fn dismiss_floating_gamepad_text_input(&self) -> Result<(), ()> {
+		if unsafe { SteamAPI_ISteamUtils_DismissFloatingGamepadTextInput(self.raw.clone()) } {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	pub fn floating_gamepad_text_input_dismissals(
+		&self,
+	) -> impl Stream<Item = FloatingGamepadTextInputDismissed, Error = Error> + 'a {
+		self.callbacks.subscribe()
+	}
+
+	pub fn is_overlay_enabled(&self) -> bool {
+		unsafe { SteamAPI_ISteamUtils_IsOverlayEnabled(self.raw.clone()) }
+	}
+
+	pub fn set_overlay_notification_position(&self, position: NotificationPosition) {
+		unsafe { SteamAPI_ISteamUtils_SetOverlayNotificationPosition(self.raw.clone(), position) }
+	}
+
+	/// Offsets the notifications from the corner they are positioned in, in pixels.
+	pub fn set_overlay_notification_inset(&self, horizontal: i32, vertical: i32) {
+		unsafe {
+			SteamAPI_ISteamUtils_SetOverlayNotificationInset(self.raw.clone(), horizontal, vertical)
+		}
+	}
+
+	pub fn image_size(&self, image: ImageHandle) -> Option<(u32, u32)> {
+		let mut width = 0;
+		let mut height = 0;
+		if unsafe {
+			SteamAPI_ISteamUtils_GetImageSize(
+				self.raw.clone(),
+				image,
+				&mut width as *mut u32,
+				&mut height as *mut u32,
+			)
+		} {
+			Some((width, height))
+		} else {
+			None
+		}
+	}
+
+	pub fn image(&self, image: ImageHandle) -> Option<Image> {
+		let (width, height) = self.image_size(image)?;
+		let mut rgba = vec![0; width as usize * height as usize * 4];
+		if unsafe {
+			SteamAPI_ISteamUtils_GetImageRGBA(
+				self.raw.clone(),
+				image,
+				rgba.as_mut_ptr(),
+				rgba.len() as i32,
+			)
+		} {
+			Some(Image {
+				width,
+				height,
+				rgba,
+			})
+		} else {
+			None
+		}
+	}
 }
 
 steam_extern! {
 	fn SteamAPI_ISteamUtils_IsAPICallCompleted(a: Raw<Utils<'_>>, b: APICall<'_>, c: *mut bool) -> bool;
 	fn SteamAPI_ISteamUtils_GetAPICallResult(a: Raw<Utils<'_>>, b: APICall<'_>, c: *mut u8, d: u32, e: u32, f: *mut bool) -> bool;
+
+	fn SteamAPI_ISteamUtils_GetAPICallFailureReason(a: Raw<Utils<'_>>, b: APICall<'_>) -> i32;
+
+	fn SteamAPI_ISteamUtils_GetAppID(a: Raw<Utils<'_>>)                        -> u32;
+	fn SteamAPI_ISteamUtils_GetServerRealTime(a: Raw<Utils<'_>>)               -> u32;
+	fn SteamAPI_ISteamUtils_GetSecondsSinceAppActive(a: Raw<Utils<'_>>)        -> u32;
+	fn SteamAPI_ISteamUtils_GetSecondsSinceComputerActive(a: Raw<Utils<'_>>)   -> u32;
+	fn SteamAPI_ISteamUtils_GetIPCountry(a: Raw<Utils<'_>>)                    -> *const c_char;
+	fn SteamAPI_ISteamUtils_GetCurrentBatteryPower(a: Raw<Utils<'_>>)          -> u8;
+	fn SteamAPI_ISteamUtils_IsSteamRunningOnSteamDeck(a: Raw<Utils<'_>>)       -> bool;
+	fn SteamAPI_ISteamUtils_IsSteamInBigPictureMode(a: Raw<Utils<'_>>)         -> bool;
+	fn SteamAPI_ISteamUtils_SetWarningMessageHook(a: Raw<Utils<'_>>, b: extern "C" fn(i32, *const c_char));
+
+	fn SteamAPI_ISteamUtils_ShowGamepadTextInput(a: Raw<Utils<'_>>, b: GamepadTextInputMode, c: GamepadTextInputLineMode, d: *const c_char, e: u32, f: *const c_char) -> bool;
+	fn SteamAPI_ISteamUtils_GetEnteredGamepadTextLength(a: Raw<Utils<'_>>)                      -> u32;
+	fn SteamAPI_ISteamUtils_GetEnteredGamepadTextInput(a: Raw<Utils<'_>>, b: *mut c_char, c: u32) -> bool;
+	fn SteamAPI_ISteamUtils_ShowFloatingGamepadTextInput(a: Raw<Utils<'_>>, b: FloatingGamepadTextInputMode, c: i32, d: i32, e: i32, f: i32) -> bool;
+	fn SteamAPI_ISteamUtils_DismissFloatingGamepadTextInput(a: Raw<Utils<'_>>) -> bool;
+
+	fn SteamAPI_ISteamUtils_IsOverlayEnabled(a: Raw<Utils<'_>>) -> bool;
+	fn SteamAPI_ISteamUtils_SetOverlayNotificationPosition(a: Raw<Utils<'_>>, b: NotificationPosition);
+	fn SteamAPI_ISteamUtils_SetOverlayNotificationInset(a: Raw<Utils<'_>>, b: i32, c: i32);
+
+	fn SteamAPI_ISteamUtils_GetImageSize(a: Raw<Utils<'_>>, b: ImageHandle, c: *mut u32, d: *mut u32) -> bool;
+	fn SteamAPI_ISteamUtils_GetImageRGBA(a: Raw<Utils<'_>>, b: ImageHandle, c: *mut u8, d: i32)       -> bool;
 }